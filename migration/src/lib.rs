@@ -2,6 +2,31 @@ pub use sea_orm_migration::prelude::*;
 
 mod m20251126_025741_create_user_table;
 mod m20251127_115730_add_auth_fields_to_users;
+mod m20251201_083000_create_refresh_tokens_table;
+mod m20251203_091500_create_revoked_tokens_table;
+mod m20251205_140000_create_user_sessions_table;
+mod m20251208_101500_add_token_version_to_users;
+mod m20251210_093000_create_server_sessions_table;
+mod m20251212_110000_add_remember_me_to_refresh_tokens;
+mod m20251215_090000_create_impersonation_events_table;
+mod m20251217_100000_create_api_keys_table;
+mod m20251219_093000_create_personal_access_tokens_table;
+mod m20251222_090000_create_oauth_clients_table;
+mod m20251222_090100_create_oauth_authorization_codes_table;
+mod m20251222_090200_add_oauth_fields_to_refresh_tokens;
+mod m20251224_100000_add_service_account_to_oauth_clients;
+mod m20251229_083000_create_user_identities_table;
+mod m20260105_090000_create_magic_links_table;
+mod m20260108_090000_add_phone_to_users;
+mod m20260108_090100_create_login_codes_table;
+mod m20260112_090000_create_user_totp_table;
+mod m20260112_090100_create_mfa_challenges_table;
+mod m20260115_090000_create_webauthn_credentials_table;
+mod m20260115_090100_create_webauthn_ceremonies_table;
+mod m20260119_090000_create_mfa_recovery_codes_table;
+mod m20260122_090000_add_phone_verified_at_to_users;
+mod m20260122_090100_add_user_handle_to_webauthn_credentials;
+mod m20260122_090200_add_verified_phone_to_users;
 
 pub struct Migrator;
 
@@ -11,6 +36,31 @@ impl MigratorTrait for Migrator {
         vec![
                 Box::new(m20251126_025741_create_user_table::Migration),
                 Box::new(m20251127_115730_add_auth_fields_to_users::Migration),
+                Box::new(m20251201_083000_create_refresh_tokens_table::Migration),
+                Box::new(m20251203_091500_create_revoked_tokens_table::Migration),
+                Box::new(m20251205_140000_create_user_sessions_table::Migration),
+                Box::new(m20251208_101500_add_token_version_to_users::Migration),
+                Box::new(m20251210_093000_create_server_sessions_table::Migration),
+                Box::new(m20251212_110000_add_remember_me_to_refresh_tokens::Migration),
+                Box::new(m20251215_090000_create_impersonation_events_table::Migration),
+                Box::new(m20251217_100000_create_api_keys_table::Migration),
+                Box::new(m20251219_093000_create_personal_access_tokens_table::Migration),
+                Box::new(m20251222_090000_create_oauth_clients_table::Migration),
+                Box::new(m20251222_090100_create_oauth_authorization_codes_table::Migration),
+                Box::new(m20251222_090200_add_oauth_fields_to_refresh_tokens::Migration),
+                Box::new(m20251224_100000_add_service_account_to_oauth_clients::Migration),
+                Box::new(m20251229_083000_create_user_identities_table::Migration),
+                Box::new(m20260105_090000_create_magic_links_table::Migration),
+                Box::new(m20260108_090000_add_phone_to_users::Migration),
+                Box::new(m20260108_090100_create_login_codes_table::Migration),
+                Box::new(m20260112_090000_create_user_totp_table::Migration),
+                Box::new(m20260112_090100_create_mfa_challenges_table::Migration),
+                Box::new(m20260115_090000_create_webauthn_credentials_table::Migration),
+                Box::new(m20260115_090100_create_webauthn_ceremonies_table::Migration),
+                Box::new(m20260119_090000_create_mfa_recovery_codes_table::Migration),
+                Box::new(m20260122_090000_add_phone_verified_at_to_users::Migration),
+                Box::new(m20260122_090100_add_user_handle_to_webauthn_credentials::Migration),
+                Box::new(m20260122_090200_add_verified_phone_to_users::Migration),
         ]
     }
 }
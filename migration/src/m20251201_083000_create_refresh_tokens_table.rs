@@ -0,0 +1,74 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(RefreshToken::Table)
+                    .if_not_exists()
+                    .col(pk_auto(RefreshToken::Id))
+                    .col(integer(RefreshToken::UserId))
+                    .col(string_len_uniq(RefreshToken::Jti, 36))
+                    .col(string_len_uniq(RefreshToken::TokenHash, 64))
+                    .col(string_len(RefreshToken::FamilyId, 36))
+                    .col(integer_null(RefreshToken::ParentId))
+                    .col(timestamp(RefreshToken::ExpiresAt))
+                    .col(boolean(RefreshToken::Revoked).default(false))
+                    .col(timestamp_null(RefreshToken::UsedAt))
+                    .col(timestamp(RefreshToken::CreatedAt).default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_refresh_tokens_user_id")
+                            .from(RefreshToken::Table, RefreshToken::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_refresh_tokens_family_id")
+                    .table(RefreshToken::Table)
+                    .col(RefreshToken::FamilyId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(RefreshToken::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum RefreshToken {
+    #[sea_orm(iden = "refresh_tokens")]
+    Table,
+    Id,
+    UserId,
+    Jti,
+    TokenHash,
+    FamilyId,
+    ParentId,
+    ExpiresAt,
+    Revoked,
+    UsedAt,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum User {
+    #[sea_orm(iden = "users")]
+    Table,
+    Id,
+}
@@ -0,0 +1,49 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(RevokedToken::Table)
+                    .if_not_exists()
+                    .col(pk_auto(RevokedToken::Id))
+                    .col(string_len_uniq(RevokedToken::Jti, 36))
+                    .col(timestamp(RevokedToken::ExpiresAt))
+                    .col(timestamp(RevokedToken::CreatedAt).default(Expr::current_timestamp()))
+                    .to_owned(),
+            )
+            .await?;
+
+        // Dipakai saat prune entry yang sudah expired
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_revoked_tokens_expires_at")
+                    .table(RevokedToken::Table)
+                    .col(RevokedToken::ExpiresAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(RevokedToken::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum RevokedToken {
+    #[sea_orm(iden = "revoked_tokens")]
+    Table,
+    Id,
+    Jti,
+    ExpiresAt,
+    CreatedAt,
+}
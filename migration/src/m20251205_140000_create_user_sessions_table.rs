@@ -0,0 +1,65 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserSession::Table)
+                    .if_not_exists()
+                    // Sama dengan family_id refresh token dari login yang membuat session ini
+                    .col(string_len(UserSession::Id, 36).primary_key())
+                    .col(integer(UserSession::UserId))
+                    .col(string_len_null(UserSession::UserAgent, 512))
+                    .col(string_len_null(UserSession::IpAddress, 64))
+                    .col(string_len_null(UserSession::AccessJti, 36))
+                    .col(timestamp_null(UserSession::AccessExpiresAt))
+                    .col(timestamp(UserSession::ExpiresAt))
+                    .col(timestamp(UserSession::CreatedAt).default(Expr::current_timestamp()))
+                    .col(timestamp(UserSession::LastUsedAt).default(Expr::current_timestamp()))
+                    .col(timestamp_null(UserSession::RevokedAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_user_sessions_user_id")
+                            .from(UserSession::Table, UserSession::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(UserSession::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum UserSession {
+    #[sea_orm(iden = "user_sessions")]
+    Table,
+    Id,
+    UserId,
+    UserAgent,
+    IpAddress,
+    AccessJti,
+    AccessExpiresAt,
+    ExpiresAt,
+    CreatedAt,
+    LastUsedAt,
+    RevokedAt,
+}
+
+#[derive(DeriveIden)]
+enum User {
+    #[sea_orm(iden = "users")]
+    Table,
+    Id,
+}
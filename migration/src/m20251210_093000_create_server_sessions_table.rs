@@ -0,0 +1,71 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ServerSession::Table)
+                    .if_not_exists()
+                    // SHA-256 dari session id di cookie
+                    .col(string_len(ServerSession::Id, 64).primary_key())
+                    .col(string_len(ServerSession::SessionId, 36))
+                    .col(integer(ServerSession::UserId))
+                    .col(string(ServerSession::Email))
+                    .col(string(ServerSession::Role))
+                    .col(integer(ServerSession::TokenVersion))
+                    .col(timestamp(ServerSession::ExpiresAt))
+                    .col(timestamp(ServerSession::CreatedAt).default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_server_sessions_user_id")
+                            .from(ServerSession::Table, ServerSession::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_server_sessions_session_id")
+                    .table(ServerSession::Table)
+                    .col(ServerSession::SessionId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ServerSession::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ServerSession {
+    #[sea_orm(iden = "server_sessions")]
+    Table,
+    Id,
+    SessionId,
+    UserId,
+    Email,
+    Role,
+    TokenVersion,
+    ExpiresAt,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum User {
+    #[sea_orm(iden = "users")]
+    Table,
+    Id,
+}
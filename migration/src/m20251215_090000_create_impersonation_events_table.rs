@@ -0,0 +1,63 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ImpersonationEvent::Table)
+                    .if_not_exists()
+                    .col(pk_auto(ImpersonationEvent::Id))
+                    .col(integer(ImpersonationEvent::AdminId))
+                    .col(integer(ImpersonationEvent::TargetUserId))
+                    .col(string_len(ImpersonationEvent::Jti, 36))
+                    .col(string_len(ImpersonationEvent::Action, 16))
+                    .col(string_len_null(ImpersonationEvent::IpAddress, 64))
+                    .col(timestamp(ImpersonationEvent::CreatedAt).default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_impersonation_events_admin_id")
+                            .from(ImpersonationEvent::Table, ImpersonationEvent::AdminId)
+                            .to(User::Table, User::Id),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_impersonation_events_target_user_id")
+                            .from(ImpersonationEvent::Table, ImpersonationEvent::TargetUserId)
+                            .to(User::Table, User::Id),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ImpersonationEvent::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ImpersonationEvent {
+    #[sea_orm(iden = "impersonation_events")]
+    Table,
+    Id,
+    AdminId,
+    TargetUserId,
+    Jti,
+    Action,
+    IpAddress,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum User {
+    #[sea_orm(iden = "users")]
+    Table,
+    Id,
+}
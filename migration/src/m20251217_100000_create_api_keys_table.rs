@@ -0,0 +1,64 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ApiKey::Table)
+                    .if_not_exists()
+                    .col(pk_auto(ApiKey::Id))
+                    .col(integer(ApiKey::UserId))
+                    .col(string_len(ApiKey::Name, 100))
+                    // Awalan key yang boleh ditampilkan, untuk mengenali key tanpa membuka secret
+                    .col(string_len(ApiKey::Prefix, 16))
+                    // SHA-256 dari key lengkap
+                    .col(string_len_uniq(ApiKey::KeyHash, 64))
+                    .col(timestamp(ApiKey::CreatedAt).default(Expr::current_timestamp()))
+                    .col(timestamp_null(ApiKey::LastUsedAt))
+                    .col(timestamp_null(ApiKey::ExpiresAt))
+                    .col(timestamp_null(ApiKey::RevokedAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_api_keys_user_id")
+                            .from(ApiKey::Table, ApiKey::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ApiKey::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ApiKey {
+    #[sea_orm(iden = "api_keys")]
+    Table,
+    Id,
+    UserId,
+    Name,
+    Prefix,
+    KeyHash,
+    CreatedAt,
+    LastUsedAt,
+    ExpiresAt,
+    RevokedAt,
+}
+
+#[derive(DeriveIden)]
+enum User {
+    #[sea_orm(iden = "users")]
+    Table,
+    Id,
+}
@@ -0,0 +1,67 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PersonalAccessToken::Table)
+                    .if_not_exists()
+                    .col(pk_auto(PersonalAccessToken::Id))
+                    .col(integer(PersonalAccessToken::UserId))
+                    .col(string_len(PersonalAccessToken::Name, 100))
+                    // Awalan token yang boleh ditampilkan, untuk mengenali token tanpa membuka secret
+                    .col(string_len(PersonalAccessToken::Prefix, 16))
+                    // SHA-256 dari token lengkap
+                    .col(string_len_uniq(PersonalAccessToken::TokenHash, 64))
+                    // Daftar scope dipisah spasi, mis. "users:read users:write"
+                    .col(string_len(PersonalAccessToken::Scopes, 512))
+                    .col(timestamp(PersonalAccessToken::CreatedAt).default(Expr::current_timestamp()))
+                    .col(timestamp_null(PersonalAccessToken::LastUsedAt))
+                    .col(timestamp(PersonalAccessToken::ExpiresAt))
+                    .col(timestamp_null(PersonalAccessToken::RevokedAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_personal_access_tokens_user_id")
+                            .from(PersonalAccessToken::Table, PersonalAccessToken::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PersonalAccessToken::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum PersonalAccessToken {
+    #[sea_orm(iden = "personal_access_tokens")]
+    Table,
+    Id,
+    UserId,
+    Name,
+    Prefix,
+    TokenHash,
+    Scopes,
+    CreatedAt,
+    LastUsedAt,
+    ExpiresAt,
+    RevokedAt,
+}
+
+#[derive(DeriveIden)]
+enum User {
+    #[sea_orm(iden = "users")]
+    Table,
+    Id,
+}
@@ -0,0 +1,46 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(OAuthClient::Table)
+                    .if_not_exists()
+                    // client_id acak yang dikirim client di setiap request OAuth
+                    .col(string_len(OAuthClient::Id, 64).primary_key())
+                    .col(string_len(OAuthClient::Name, 100))
+                    // SHA-256 client secret, NULL untuk public client (SPA / mobile)
+                    .col(string_len_null(OAuthClient::SecretHash, 64))
+                    // Redirect URI yang diizinkan, dipisah spasi, dicocokkan persis
+                    .col(text(OAuthClient::RedirectUris))
+                    // Scope maksimal yang boleh diminta client, dipisah spasi
+                    .col(string_len(OAuthClient::Scopes, 512))
+                    .col(timestamp(OAuthClient::CreatedAt).default(Expr::current_timestamp()))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(OAuthClient::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum OAuthClient {
+    #[sea_orm(iden = "oauth_clients")]
+    Table,
+    Id,
+    Name,
+    SecretHash,
+    RedirectUris,
+    Scopes,
+    CreatedAt,
+}
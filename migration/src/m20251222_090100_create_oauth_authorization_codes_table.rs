@@ -0,0 +1,80 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(OAuthAuthorizationCode::Table)
+                    .if_not_exists()
+                    .col(pk_auto(OAuthAuthorizationCode::Id))
+                    // SHA-256 dari code, code mentah hanya dikirim ke redirect_uri
+                    .col(string_len_uniq(OAuthAuthorizationCode::CodeHash, 64))
+                    .col(string_len(OAuthAuthorizationCode::ClientId, 64))
+                    .col(integer(OAuthAuthorizationCode::UserId))
+                    .col(string_len(OAuthAuthorizationCode::RedirectUri, 512))
+                    .col(string_len(OAuthAuthorizationCode::Scopes, 512))
+                    // PKCE (RFC 7636), hanya S256
+                    .col(string_len(OAuthAuthorizationCode::CodeChallenge, 128))
+                    .col(timestamp(OAuthAuthorizationCode::ExpiresAt))
+                    .col(timestamp_null(OAuthAuthorizationCode::UsedAt))
+                    .col(timestamp(OAuthAuthorizationCode::CreatedAt).default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_oauth_authorization_codes_client_id")
+                            .from(OAuthAuthorizationCode::Table, OAuthAuthorizationCode::ClientId)
+                            .to(OAuthClient::Table, OAuthClient::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_oauth_authorization_codes_user_id")
+                            .from(OAuthAuthorizationCode::Table, OAuthAuthorizationCode::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(OAuthAuthorizationCode::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum OAuthAuthorizationCode {
+    #[sea_orm(iden = "oauth_authorization_codes")]
+    Table,
+    Id,
+    CodeHash,
+    ClientId,
+    UserId,
+    RedirectUri,
+    Scopes,
+    CodeChallenge,
+    ExpiresAt,
+    UsedAt,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum OAuthClient {
+    #[sea_orm(iden = "oauth_clients")]
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum User {
+    #[sea_orm(iden = "users")]
+    Table,
+    Id,
+}
@@ -0,0 +1,40 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(RefreshToken::Table)
+                    // Diisi untuk refresh token yang diterbitkan lewat /oauth/token
+                    .add_column(string_len_null(RefreshToken::ClientId, 64))
+                    .add_column(string_len_null(RefreshToken::Scopes, 512))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(RefreshToken::Table)
+                    .drop_column(RefreshToken::ClientId)
+                    .drop_column(RefreshToken::Scopes)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum RefreshToken {
+    #[sea_orm(iden = "refresh_tokens")]
+    Table,
+    ClientId,
+    Scopes,
+}
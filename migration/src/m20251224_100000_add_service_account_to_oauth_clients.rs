@@ -0,0 +1,37 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(OAuthClient::Table)
+                    // Service account hanya memakai grant client_credentials, tanpa user
+                    .add_column(boolean(OAuthClient::ServiceAccount).default(false))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(OAuthClient::Table)
+                    .drop_column(OAuthClient::ServiceAccount)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum OAuthClient {
+    #[sea_orm(iden = "oauth_clients")]
+    Table,
+    ServiceAccount,
+}
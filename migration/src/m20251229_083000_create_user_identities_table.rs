@@ -0,0 +1,67 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserIdentity::Table)
+                    .if_not_exists()
+                    .col(pk_auto(UserIdentity::Id))
+                    .col(integer(UserIdentity::UserId))
+                    // `iss` dari IdP, satu user bisa punya identitas di beberapa IdP
+                    .col(string_len(UserIdentity::Issuer, 255))
+                    // `sub` dari IdP, stabil walaupun email berubah
+                    .col(string_len(UserIdentity::Subject, 255))
+                    .col(string_len_null(UserIdentity::Email, 255))
+                    .col(timestamp(UserIdentity::CreatedAt).default(Expr::current_timestamp()))
+                    .col(timestamp_null(UserIdentity::LastLoginAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_user_identities_user_id")
+                            .from(UserIdentity::Table, UserIdentity::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_user_identities_issuer_subject")
+                            .col(UserIdentity::Issuer)
+                            .col(UserIdentity::Subject)
+                            .unique(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(UserIdentity::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum UserIdentity {
+    #[sea_orm(iden = "user_identities")]
+    Table,
+    Id,
+    UserId,
+    Issuer,
+    Subject,
+    Email,
+    CreatedAt,
+    LastLoginAt,
+}
+
+#[derive(DeriveIden)]
+enum User {
+    #[sea_orm(iden = "users")]
+    Table,
+    Id,
+}
@@ -0,0 +1,62 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(MagicLink::Table)
+                    .if_not_exists()
+                    .col(pk_auto(MagicLink::Id))
+                    .col(integer(MagicLink::UserId))
+                    // SHA-256 dari token di link, token aslinya hanya ada di email
+                    .col(string_len_uniq(MagicLink::TokenHash, 64))
+                    .col(boolean(MagicLink::RememberMe).default(false))
+                    .col(string_len_null(MagicLink::IpAddress, 45))
+                    .col(timestamp(MagicLink::CreatedAt).default(Expr::current_timestamp()))
+                    .col(timestamp(MagicLink::ExpiresAt))
+                    // Terisi saat link dipakai (atau digantikan link baru)
+                    .col(timestamp_null(MagicLink::UsedAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_magic_links_user_id")
+                            .from(MagicLink::Table, MagicLink::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(MagicLink::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum MagicLink {
+    #[sea_orm(iden = "magic_links")]
+    Table,
+    Id,
+    UserId,
+    TokenHash,
+    RememberMe,
+    IpAddress,
+    CreatedAt,
+    ExpiresAt,
+    UsedAt,
+}
+
+#[derive(DeriveIden)]
+enum User {
+    #[sea_orm(iden = "users")]
+    Table,
+    Id,
+}
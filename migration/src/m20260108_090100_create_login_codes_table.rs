@@ -0,0 +1,63 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(LoginCode::Table)
+                    .if_not_exists()
+                    .col(pk_auto(LoginCode::Id))
+                    .col(integer(LoginCode::UserId))
+                    // "email" atau "sms"
+                    .col(string_len(LoginCode::Channel, 16))
+                    // bcrypt dari kode 6 digit
+                    .col(string_len(LoginCode::CodeHash, 255))
+                    .col(integer(LoginCode::Attempts).default(0))
+                    .col(timestamp(LoginCode::CreatedAt).default(Expr::current_timestamp()))
+                    .col(timestamp(LoginCode::ExpiresAt))
+                    // Terisi saat kode dipakai (atau digantikan kode baru)
+                    .col(timestamp_null(LoginCode::ConsumedAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_login_codes_user_id")
+                            .from(LoginCode::Table, LoginCode::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(LoginCode::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum LoginCode {
+    #[sea_orm(iden = "login_codes")]
+    Table,
+    Id,
+    UserId,
+    Channel,
+    CodeHash,
+    Attempts,
+    CreatedAt,
+    ExpiresAt,
+    ConsumedAt,
+}
+
+#[derive(DeriveIden)]
+enum User {
+    #[sea_orm(iden = "users")]
+    Table,
+    Id,
+}
@@ -0,0 +1,60 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserTotp::Table)
+                    .if_not_exists()
+                    .col(pk_auto(UserTotp::Id))
+                    // Satu authenticator per user
+                    .col(integer_uniq(UserTotp::UserId))
+                    // Seed TOTP, dienkripsi AES-GCM dengan MFA_ENCRYPTION_KEY
+                    .col(string_len(UserTotp::SecretEncrypted, 255))
+                    // Null = enrollment belum dikonfirmasi dengan kode pertama
+                    .col(timestamp_null(UserTotp::ConfirmedAt))
+                    // Step TOTP terakhir yang dipakai, kode yang sama tidak bisa dipakai ulang
+                    .col(big_integer_null(UserTotp::LastUsedStep))
+                    .col(timestamp(UserTotp::CreatedAt).default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_user_totp_user_id")
+                            .from(UserTotp::Table, UserTotp::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(UserTotp::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum UserTotp {
+    #[sea_orm(iden = "user_totp")]
+    Table,
+    Id,
+    UserId,
+    SecretEncrypted,
+    ConfirmedAt,
+    LastUsedStep,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum User {
+    #[sea_orm(iden = "users")]
+    Table,
+    Id,
+}
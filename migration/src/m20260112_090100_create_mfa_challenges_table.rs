@@ -0,0 +1,61 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(MfaChallenge::Table)
+                    .if_not_exists()
+                    .col(pk_auto(MfaChallenge::Id))
+                    .col(integer(MfaChallenge::UserId))
+                    // SHA-256 dari challenge token yang dikirim ke client setelah faktor pertama
+                    .col(string_len_uniq(MfaChallenge::TokenHash, 64))
+                    .col(boolean(MfaChallenge::RememberMe).default(false))
+                    .col(integer(MfaChallenge::Attempts).default(0))
+                    .col(timestamp(MfaChallenge::CreatedAt).default(Expr::current_timestamp()))
+                    .col(timestamp(MfaChallenge::ExpiresAt))
+                    .col(timestamp_null(MfaChallenge::ConsumedAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_mfa_challenges_user_id")
+                            .from(MfaChallenge::Table, MfaChallenge::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(MfaChallenge::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum MfaChallenge {
+    #[sea_orm(iden = "mfa_challenges")]
+    Table,
+    Id,
+    UserId,
+    TokenHash,
+    RememberMe,
+    Attempts,
+    CreatedAt,
+    ExpiresAt,
+    ConsumedAt,
+}
+
+#[derive(DeriveIden)]
+enum User {
+    #[sea_orm(iden = "users")]
+    Table,
+    Id,
+}
@@ -0,0 +1,66 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(WebauthnCredential::Table)
+                    .if_not_exists()
+                    .col(pk_auto(WebauthnCredential::Id))
+                    .col(integer(WebauthnCredential::UserId))
+                    .col(string_len(WebauthnCredential::Name, 100))
+                    // Credential id dari authenticator, base64url
+                    .col(string_len_uniq(WebauthnCredential::CredentialId, 512))
+                    // COSE public key (JSON)
+                    .col(text(WebauthnCredential::PublicKey))
+                    // Signature counter terakhir, counter yang mundur = kemungkinan authenticator dikloning
+                    .col(big_integer(WebauthnCredential::SignCount).default(0))
+                    // State lengkap credential dari webauthn-rs (JSON), dipakai saat verifikasi
+                    .col(text(WebauthnCredential::Passkey))
+                    .col(timestamp(WebauthnCredential::CreatedAt).default(Expr::current_timestamp()))
+                    .col(timestamp_null(WebauthnCredential::LastUsedAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_webauthn_credentials_user_id")
+                            .from(WebauthnCredential::Table, WebauthnCredential::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(WebauthnCredential::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum WebauthnCredential {
+    #[sea_orm(iden = "webauthn_credentials")]
+    Table,
+    Id,
+    UserId,
+    Name,
+    CredentialId,
+    PublicKey,
+    SignCount,
+    Passkey,
+    CreatedAt,
+    LastUsedAt,
+}
+
+#[derive(DeriveIden)]
+enum User {
+    #[sea_orm(iden = "users")]
+    Table,
+    Id,
+}
@@ -0,0 +1,60 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(WebauthnCeremony::Table)
+                    .if_not_exists()
+                    // UUID acak yang dikirim ke client bersama options
+                    .col(string_len(WebauthnCeremony::Id, 36).primary_key())
+                    // Null untuk login passkey tanpa username (user belum diketahui)
+                    .col(integer_null(WebauthnCeremony::UserId))
+                    // "register", "authenticate" atau "discover"
+                    .col(string_len(WebauthnCeremony::Kind, 16))
+                    // State ceremony dari webauthn-rs (JSON), berisi challenge yang harus ditandatangani
+                    .col(text(WebauthnCeremony::State))
+                    .col(timestamp(WebauthnCeremony::CreatedAt).default(Expr::current_timestamp()))
+                    .col(timestamp(WebauthnCeremony::ExpiresAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_webauthn_ceremonies_user_id")
+                            .from(WebauthnCeremony::Table, WebauthnCeremony::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(WebauthnCeremony::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum WebauthnCeremony {
+    #[sea_orm(iden = "webauthn_ceremonies")]
+    Table,
+    Id,
+    UserId,
+    Kind,
+    State,
+    CreatedAt,
+    ExpiresAt,
+}
+
+#[derive(DeriveIden)]
+enum User {
+    #[sea_orm(iden = "users")]
+    Table,
+    Id,
+}
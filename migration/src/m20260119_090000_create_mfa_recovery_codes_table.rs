@@ -0,0 +1,55 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(MfaRecoveryCode::Table)
+                    .if_not_exists()
+                    .col(pk_auto(MfaRecoveryCode::Id))
+                    .col(integer(MfaRecoveryCode::UserId))
+                    // SHA-256 dari kode (80 bit acak), kode aslinya hanya ditampilkan sekali ke user
+                    .col(string_len_uniq(MfaRecoveryCode::CodeHash, 64))
+                    .col(timestamp(MfaRecoveryCode::CreatedAt).default(Expr::current_timestamp()))
+                    .col(timestamp_null(MfaRecoveryCode::UsedAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_mfa_recovery_codes_user_id")
+                            .from(MfaRecoveryCode::Table, MfaRecoveryCode::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(MfaRecoveryCode::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum MfaRecoveryCode {
+    #[sea_orm(iden = "mfa_recovery_codes")]
+    Table,
+    Id,
+    UserId,
+    CodeHash,
+    CreatedAt,
+    UsedAt,
+}
+
+#[derive(DeriveIden)]
+enum User {
+    #[sea_orm(iden = "users")]
+    Table,
+    Id,
+}
@@ -0,0 +1,67 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    // Nomor telepon baru dipakai untuk login OTP setelah dikonfirmasi lewat SMS
+                    .add_column(timestamp_null(User::PhoneVerifiedAt))
+                    .to_owned(),
+            )
+            .await?;
+
+        // Nomor yang belum diverifikasi tidak boleh mengunci nomor itu untuk pemilik aslinya,
+        // jadi unique index diganti index biasa; keunikan nomor terverifikasi dijaga lewat
+        // users.verified_phone (m20260122_090200)
+        manager
+            .drop_index(Index::drop().name("phone").table(User::Table).to_owned())
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_users_phone")
+                    .table(User::Table)
+                    .col(User::Phone)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(Index::drop().name("idx_users_phone").table(User::Table).to_owned())
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("phone")
+                    .table(User::Table)
+                    .col(User::Phone)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .drop_column(User::PhoneVerifiedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum User {
+    #[sea_orm(iden = "users")]
+    Table,
+    Phone,
+    PhoneVerifiedAt,
+}
@@ -0,0 +1,67 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(WebauthnCredential::Table)
+                    // User handle acak (UUID) yang disimpan di authenticator, bukan turunan id user
+                    .add_column(string_len_null(WebauthnCredential::UserHandle, 36))
+                    .to_owned(),
+            )
+            .await?;
+
+        // Credential lama sudah tersimpan di authenticator dengan handle Uuid::from_u128(user_id),
+        // handle itu dipertahankan supaya login passkey yang ada tetap jalan
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "UPDATE webauthn_credentials SET user_handle = LOWER(INSERT(INSERT(INSERT(INSERT(\
+                 LPAD(HEX(user_id), 32, '0'), 21, 0, '-'), 17, 0, '-'), 13, 0, '-'), 9, 0, '-')) \
+                 WHERE user_handle IS NULL",
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(WebauthnCredential::Table)
+                    .modify_column(string_len(WebauthnCredential::UserHandle, 36))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_webauthn_credentials_user_handle")
+                    .table(WebauthnCredential::Table)
+                    .col(WebauthnCredential::UserHandle)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(WebauthnCredential::Table)
+                    .drop_column(WebauthnCredential::UserHandle)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum WebauthnCredential {
+    #[sea_orm(iden = "webauthn_credentials")]
+    Table,
+    UserHandle,
+}
@@ -0,0 +1,52 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Nomor terverifikasi harus unik di database, bukan hanya dicek di aplikasi:
+        // kolom generated berisi phone hanya kalau phone_verified_at terisi, NULL tidak bentrok
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "ALTER TABLE users \
+                 ADD COLUMN verified_phone VARCHAR(16) \
+                 GENERATED ALWAYS AS (IF(phone_verified_at IS NULL, NULL, phone)) STORED",
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_users_verified_phone")
+                    .table(User::Table)
+                    .col(User::VerifiedPhone)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(Index::drop().name("idx_users_verified_phone").table(User::Table).to_owned())
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .drop_column(User::VerifiedPhone)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum User {
+    #[sea_orm(iden = "users")]
+    Table,
+    VerifiedPhone,
+}
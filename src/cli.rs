@@ -0,0 +1,60 @@
+use chrono::Duration;
+use crate::utils::keys;
+
+// Perintah admin lewat command line, tanpa menjalankan server:
+//   actix_server keys list
+//   actix_server keys promote <kid> [grace_secs]
+//   actix_server keys retire <kid>
+//   actix_server keys sweep
+pub fn run(args: &[String]) -> i32 {
+    let result = match args {
+        [cmd, rest @ ..] if cmd == "keys" => run_keys(rest),
+        _ => Err(usage()),
+    };
+
+    match result {
+        Ok(()) => 0,
+        Err(err) => {
+            eprintln!("{}", err);
+            1
+        }
+    }
+}
+
+fn run_keys(args: &[String]) -> Result<(), String> {
+    match args {
+        [cmd] if cmd == "list" => {
+            for entry in keys::entries() {
+                let retire_at = entry.retire_at.map(|at| at.to_rfc3339()).unwrap_or_default();
+                println!("{}\t{:?}\t{:?}\t{}", entry.kid, entry.algorithm, entry.status, retire_at);
+            }
+            Ok(())
+        }
+        [cmd, kid] if cmd == "promote" => {
+            keys::promote(kid, keys::default_grace())?;
+            println!("Key {} is now active", kid);
+            Ok(())
+        }
+        [cmd, kid, grace] if cmd == "promote" => {
+            let grace: i64 = grace.parse().map_err(|_| "grace_secs must be a number".to_string())?;
+            keys::promote(kid, Duration::seconds(grace))?;
+            println!("Key {} is now active", kid);
+            Ok(())
+        }
+        [cmd, kid] if cmd == "retire" => {
+            keys::retire(kid)?;
+            println!("Key {} retired", kid);
+            Ok(())
+        }
+        [cmd] if cmd == "sweep" => {
+            let swept = keys::sweep_retired()?;
+            println!("{} key(s) retired", swept);
+            Ok(())
+        }
+        _ => Err(usage()),
+    }
+}
+
+fn usage() -> String {
+    "Usage: actix_server keys <list | promote <kid> [grace_secs] | retire <kid> | sweep>".to_string()
+}
@@ -0,0 +1,76 @@
+use std::env;
+
+// Cara browser membawa identitas user:
+//   AUTH_MODE=jwt      access & refresh JWT di cookie / Authorization header (default)
+//   AUTH_MODE=session  session id acak di cookie, data session di server
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AuthMode {
+    Jwt,
+    Session,
+}
+
+impl AuthMode {
+    pub fn from_env() -> Self {
+        match env::var("AUTH_MODE").unwrap_or_default().as_str() {
+            "session" => AuthMode::Session,
+            _ => AuthMode::Jwt,
+        }
+    }
+}
+
+fn env_secs(var: &str, default: i64) -> i64 {
+    env::var(var).ok().and_then(|v| v.parse().ok()).unwrap_or(default)
+}
+
+// Umur access token dalam detik (ACCESS_TOKEN_TTL_SECS, default 1 jam)
+pub fn access_token_ttl_secs() -> i64 {
+    env_secs("ACCESS_TOKEN_TTL_SECS", 3600)
+}
+
+// Umur refresh token / session server-side dalam detik:
+//   REFRESH_TOKEN_TTL_SECS  tanpa remember me, default 1 hari (cookie hilang saat browser ditutup)
+//   REMEMBER_ME_TTL_SECS    dengan remember me, default 7 hari (cookie persistent)
+pub fn refresh_token_ttl_secs(remember_me: bool) -> i64 {
+    if remember_me {
+        env_secs("REMEMBER_ME_TTL_SECS", 7 * 24 * 3600)
+    } else {
+        env_secs("REFRESH_TOKEN_TTL_SECS", 24 * 3600)
+    }
+}
+
+// Umur access token impersonation admin dalam detik (IMPERSONATION_TTL_SECS, default 15 menit)
+pub fn impersonation_ttl_secs() -> i64 {
+    env_secs("IMPERSONATION_TTL_SECS", 15 * 60)
+}
+
+// Umur authorization code OAuth dalam detik (OAUTH_CODE_TTL_SECS, default 1 menit)
+pub fn oauth_code_ttl_secs() -> i64 {
+    env_secs("OAUTH_CODE_TTL_SECS", 60)
+}
+
+// Umur refresh token yang diterbitkan ke client OAuth (OAUTH_REFRESH_TOKEN_TTL_SECS, default 30 hari)
+pub fn oauth_refresh_token_ttl_secs() -> i64 {
+    env_secs("OAUTH_REFRESH_TOKEN_TTL_SECS", 30 * 24 * 3600)
+}
+
+// Umur magic link login dalam detik (MAGIC_LINK_TTL_SECS, default 15 menit)
+pub fn magic_link_ttl_secs() -> i64 {
+    env_secs("MAGIC_LINK_TTL_SECS", 15 * 60)
+}
+
+// Halaman frontend yang dibuka dari email (MAGIC_LINK_URL), token ditambahkan sebagai ?token=...
+// Halaman itu yang memanggil POST /api/auth/magic-link/verify, supaya link scanner di
+// email client (yang hanya melakukan GET) tidak menghabiskan link sekali pakai.
+pub fn magic_link_url() -> String {
+    env::var("MAGIC_LINK_URL").unwrap_or("http://localhost:3000/magic-link".to_string())
+}
+
+// Umur kode login OTP dalam detik (OTP_TTL_SECS, default 5 menit)
+pub fn otp_ttl_secs() -> i64 {
+    env_secs("OTP_TTL_SECS", 5 * 60)
+}
+
+// Umur challenge MFA (jeda antara faktor pertama dan kedua) dalam detik (MFA_CHALLENGE_TTL_SECS, default 5 menit)
+pub fn mfa_challenge_ttl_secs() -> i64 {
+    env_secs("MFA_CHALLENGE_TTL_SECS", 5 * 60)
+}
@@ -0,0 +1,119 @@
+use actix_web::cookie::{Cookie, SameSite, time::Duration as CookieDuration};
+use std::env;
+use std::sync::OnceLock;
+
+// Kebijakan cookie auth, diatur lewat env:
+//   COOKIE_SECURE         "true" untuk flag Secure (wajib di production dengan HTTPS)
+//   COOKIE_SAME_SITE      Lax (default), Strict atau None (None memaksa Secure)
+//   COOKIE_DOMAIN         Domain cookie, kosong = host-only
+//   COOKIE_PREFIX         "", "__Secure-" atau "__Host-" (memaksa Secure, tanpa Domain)
+//   REFRESH_COOKIE_PATH   Path cookie refresh token (default /api/auth/refresh)
+//
+// Semua cookie dibuat dan dihapus lewat sini supaya atribut saat set & clear selalu sama;
+// browser hanya menghapus cookie kalau name, path dan domain-nya cocok.
+pub struct CookiePolicy {
+    pub secure: bool,
+    pub same_site: SameSite,
+    pub domain: Option<String>,
+    pub prefix: String,
+    pub refresh_path: String,
+}
+
+pub const ACCESS_TOKEN: &str = "access_token";
+pub const REFRESH_TOKEN: &str = "refresh_token";
+pub const SESSION_ID: &str = "session_id";
+pub const CSRF_TOKEN: &str = "csrf_token";
+// State login OIDC (state, nonce, PKCE verifier) selama browser berada di IdP
+pub const OIDC_STATE: &str = "oidc_state";
+
+static POLICY: OnceLock<CookiePolicy> = OnceLock::new();
+
+pub fn policy() -> &'static CookiePolicy {
+    POLICY.get_or_init(CookiePolicy::from_env)
+}
+
+impl CookiePolicy {
+    fn from_env() -> Self {
+        let prefix = env::var("COOKIE_PREFIX").unwrap_or_default();
+        let same_site = match env::var("COOKIE_SAME_SITE").unwrap_or_default().to_lowercase().as_str() {
+            "strict" => SameSite::Strict,
+            "none" => SameSite::None,
+            _ => SameSite::Lax,
+        };
+        let mut secure = env::var("COOKIE_SECURE").map(|v| v == "true").unwrap_or(false);
+        let mut domain = env::var("COOKIE_DOMAIN").ok().filter(|d| !d.is_empty());
+
+        // Aturan browser: prefix __Secure-/__Host- dan SameSite=None wajib Secure,
+        // __Host- juga tidak boleh punya Domain
+        if prefix.starts_with("__") || same_site == SameSite::None {
+            secure = true;
+        }
+        if prefix == "__Host-" {
+            domain = None;
+        }
+
+        Self {
+            secure,
+            same_site,
+            domain,
+            prefix,
+            refresh_path: env::var("REFRESH_COOKIE_PATH").unwrap_or("/api/auth/refresh".to_string()),
+        }
+    }
+
+    fn path_for(&self, base: &str) -> String {
+        if base == REFRESH_TOKEN {
+            self.refresh_path.clone()
+        } else {
+            "/".to_string()
+        }
+    }
+
+    // Nama cookie lengkap dengan prefix. __Host- mensyaratkan Path=/, jadi cookie
+    // refresh yang path-nya dipersempit memakai __Secure- sebagai gantinya.
+    pub fn name(&self, base: &str) -> String {
+        if self.prefix == "__Host-" && self.path_for(base) != "/" {
+            format!("__Secure-{}", base)
+        } else {
+            format!("{}{}", self.prefix, base)
+        }
+    }
+
+    // `max_age` None = session cookie, hilang saat browser ditutup
+    pub fn build(&self, base: &str, value: String, max_age: Option<CookieDuration>) -> Cookie<'static> {
+        let mut cookie = Cookie::build(self.name(base), value)
+            .path(self.path_for(base))
+            // CSRF token harus bisa dibaca client untuk dikirim ulang di header
+            .http_only(base != CSRF_TOKEN)
+            .secure(self.secure)
+            .same_site(self.same_site)
+            .finish();
+
+        if let Some(domain) = &self.domain {
+            cookie.set_domain(domain.clone());
+        }
+        if let Some(max_age) = max_age {
+            cookie.set_max_age(max_age);
+        }
+        cookie
+    }
+
+    pub fn clear(&self, base: &str) -> Cookie<'static> {
+        self.build(base, String::new(), Some(CookieDuration::ZERO))
+    }
+
+    // Semua cookie auth, untuk logout
+    pub fn clear_all(&self) -> Vec<Cookie<'static>> {
+        [ACCESS_TOKEN, REFRESH_TOKEN, SESSION_ID, CSRF_TOKEN]
+            .iter()
+            .map(|base| self.clear(base))
+            .collect()
+    }
+}
+
+// Ambil nilai cookie auth dari request sesuai nama di policy
+pub fn get(req: &actix_web::HttpRequest, base: &str) -> Option<String> {
+    req.cookie(&policy().name(base))
+        .map(|c| c.value().to_string())
+        .filter(|v| !v.is_empty())
+}
@@ -0,0 +1,41 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::dtos::auth_dto::UserInfo;
+use crate::utils::jwt::Actor;
+use crate::utils::keys::{KeyEntry, KeyStatus};
+
+// Info signing key untuk admin (tanpa secret / path private key)
+#[derive(Serialize)]
+pub struct SigningKeyInfo {
+    pub kid: String,
+    pub algorithm: String,
+    pub status: KeyStatus,
+    pub retire_at: Option<DateTime<Utc>>,
+}
+
+impl From<KeyEntry> for SigningKeyInfo {
+    fn from(entry: KeyEntry) -> Self {
+        Self {
+            kid: entry.kid,
+            algorithm: format!("{:?}", entry.algorithm),
+            status: entry.status,
+            retire_at: entry.retire_at,
+        }
+    }
+}
+
+// Request promote key, grace period default dari JWT_KEY_GRACE_SECS
+#[derive(Deserialize)]
+pub struct PromoteKeyRequest {
+    pub grace_secs: Option<i64>,
+}
+
+// Response impersonation: access token pendek untuk user target, tanpa refresh token
+#[derive(Serialize)]
+pub struct ImpersonationResponse {
+    pub access_token: String,
+    pub token_type: String,
+    pub expires_in: i64,
+    pub user: UserInfo,
+    pub impersonator: Actor,
+}
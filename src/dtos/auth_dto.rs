@@ -1,4 +1,7 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use crate::entity::{api_key, personal_access_token};
+use crate::utils::scope;
 
 // Request untuk register
 #[derive(Deserialize)]
@@ -6,6 +9,9 @@ pub struct RegisterRequest {
     pub username: String,
     pub email: String,
     pub password: String,
+    // Opsional, format E.164 untuk login OTP via SMS
+    #[serde(default)]
+    pub phone: Option<String>,
 }
 
 // Request untuk login
@@ -13,6 +19,52 @@ pub struct RegisterRequest {
 pub struct LoginRequest {
     pub email: String,
     pub password: String,
+    // true = refresh cookie persistent dengan umur panjang
+    #[serde(default)]
+    pub remember_me: bool,
+}
+
+// Request magic link login, link dikirim ke email
+#[derive(Deserialize)]
+pub struct MagicLinkRequest {
+    pub email: String,
+    #[serde(default)]
+    pub remember_me: bool,
+}
+
+// Tukar token dari magic link dengan access & refresh token
+#[derive(Deserialize)]
+pub struct MagicLinkVerifyRequest {
+    pub token: String,
+}
+
+// Minta kode login OTP, isi salah satu: email atau phone
+#[derive(Deserialize)]
+pub struct LoginCodeRequest {
+    pub email: Option<String>,
+    pub phone: Option<String>,
+}
+
+// Tukar kode OTP dengan access & refresh token
+#[derive(Deserialize)]
+pub struct LoginCodeVerifyRequest {
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub code: String,
+    #[serde(default)]
+    pub remember_me: bool,
+}
+
+// Kirim kode verifikasi ke nomor telepon user. Isi `phone` untuk menambah / mengganti nomor;
+// kosong = verifikasi nomor yang sudah tersimpan
+#[derive(Deserialize)]
+pub struct PhoneVerificationRequest {
+    pub phone: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct PhoneVerificationConfirmRequest {
+    pub code: String,
 }
 
 // Response setelah berhasil register (tanpa token)
@@ -31,6 +83,14 @@ pub struct LoginResponse {
     pub user: UserInfo,
 }
 
+// Response login saat AUTH_MODE=session, session id hanya dikirim lewat cookie
+#[derive(Serialize)]
+pub struct SessionLoginResponse {
+    pub token_type: String,
+    pub expires_in: i64,
+    pub user: UserInfo,
+}
+
 #[derive(Serialize)]
 pub struct UserInfo {
     pub id: i32,
@@ -38,3 +98,163 @@ pub struct UserInfo {
     pub email: String,
     pub role: String,
 }
+
+// Request untuk refresh token (opsional, bisa juga lewat cookie refresh_token)
+#[derive(Deserialize)]
+pub struct RefreshRequest {
+    pub refresh_token: Option<String>,
+}
+
+// Request introspection (RFC 7662), dikirim sebagai form urlencoded
+#[derive(Deserialize)]
+pub struct IntrospectionRequest {
+    pub token: String,
+    #[allow(dead_code)]
+    pub token_type_hint: Option<String>,
+}
+
+// Response introspection (RFC 7662), field opsional tidak dikirim kalau token tidak aktif
+#[derive(Serialize, Default)]
+pub struct IntrospectionResponse {
+    pub active: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exp: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iat: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nbf: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aud: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iss: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jti: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+}
+
+// Info session (device) yang sedang login
+#[derive(Serialize)]
+pub struct SessionInfo {
+    pub id: String,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub expires_at: DateTime<Utc>,
+    pub current: bool,
+}
+
+// Request membuat API key baru
+#[derive(Deserialize)]
+pub struct CreateApiKeyRequest {
+    pub name: String,
+    // Kosong = tidak pernah expired
+    pub expires_in_days: Option<i64>,
+}
+
+// Info API key tanpa secret
+#[derive(Serialize)]
+pub struct ApiKeyInfo {
+    pub id: i32,
+    pub name: String,
+    pub prefix: String,
+    pub created_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl From<api_key::Model> for ApiKeyInfo {
+    fn from(key: api_key::Model) -> Self {
+        Self {
+            id: key.id,
+            name: key.name,
+            prefix: key.prefix,
+            created_at: key.created_at,
+            last_used_at: key.last_used_at,
+            expires_at: key.expires_at,
+        }
+    }
+}
+
+// Response setelah membuat API key, key lengkap hanya ditampilkan sekali ini
+#[derive(Serialize)]
+pub struct CreateApiKeyResponse {
+    pub key: String,
+    #[serde(flatten)]
+    pub info: ApiKeyInfo,
+}
+
+// Request membuat personal access token
+#[derive(Deserialize)]
+pub struct CreatePersonalTokenRequest {
+    pub name: String,
+    pub scopes: Vec<String>,
+    // Default 30 hari
+    pub expires_in_days: Option<i64>,
+}
+
+// Request ganti nama personal access token
+#[derive(Deserialize)]
+pub struct UpdatePersonalTokenRequest {
+    pub name: String,
+}
+
+// Info personal access token tanpa secret
+#[derive(Serialize)]
+pub struct PersonalTokenInfo {
+    pub id: i32,
+    pub name: String,
+    pub prefix: String,
+    pub scopes: Vec<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl From<personal_access_token::Model> for PersonalTokenInfo {
+    fn from(token: personal_access_token::Model) -> Self {
+        Self {
+            id: token.id,
+            name: token.name,
+            prefix: token.prefix,
+            scopes: scope::parse(&token.scopes),
+            created_at: token.created_at,
+            last_used_at: token.last_used_at,
+            expires_at: token.expires_at,
+        }
+    }
+}
+
+// Response setelah membuat personal access token, token lengkap hanya ditampilkan sekali ini
+#[derive(Serialize)]
+pub struct CreatePersonalTokenResponse {
+    pub token: String,
+    #[serde(flatten)]
+    pub info: PersonalTokenInfo,
+}
+
+// Query /api/auth/oidc/login
+#[derive(Deserialize)]
+pub struct OidcLoginQuery {
+    #[serde(default)]
+    pub remember_me: bool,
+}
+
+// Query callback dari IdP
+#[derive(Deserialize)]
+pub struct OidcCallbackQuery {
+    pub code: Option<String>,
+    pub state: Option<String>,
+    pub error: Option<String>,
+}
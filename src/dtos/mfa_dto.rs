@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+use webauthn_rs::prelude::PublicKeyCredential;
+
+// Response login saat user punya MFA: token belum diterbitkan, client harus
+// mengirim challenge_token + kode ke POST /api/auth/mfa/verify
+#[derive(Serialize)]
+pub struct MfaChallengeResponse {
+    pub mfa_required: bool,
+    pub challenge_token: String,
+    // Faktor kedua yang bisa dipakai, mis. ["totp", "webauthn", "recovery_code"]
+    pub methods: Vec<String>,
+    pub expires_in: i64,
+}
+
+// Isi salah satu: kode TOTP, atau kode cadangan kalau authenticator hilang
+#[derive(Deserialize)]
+pub struct MfaVerifyRequest {
+    pub challenge_token: String,
+    pub code: Option<String>,
+    pub recovery_code: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct MfaStatusResponse {
+    pub totp_enabled: bool,
+    pub webauthn_enabled: bool,
+    pub recovery_codes_remaining: u64,
+}
+
+// Kode cadangan baru, hanya ditampilkan sekali ini
+#[derive(Serialize)]
+pub struct RecoveryCodesResponse {
+    pub recovery_codes: Vec<String>,
+}
+
+// Hasil enrollment TOTP, ditampilkan sekali sebagai QR code / secret manual
+#[derive(Serialize)]
+pub struct TotpSetupResponse {
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+// Kode dari authenticator, untuk konfirmasi enrollment TOTP
+#[derive(Deserialize)]
+pub struct TotpCodeRequest {
+    pub code: String,
+}
+
+// Konfirmasi ulang faktor kedua untuk aksi sensitif. Isi salah satu: kode TOTP, kode cadangan,
+// atau assertion WebAuthn dari POST /api/auth/webauthn/reauth/start (ceremony_id + credential)
+#[derive(Deserialize)]
+pub struct SecondFactorProof {
+    pub code: Option<String>,
+    pub recovery_code: Option<String>,
+    pub ceremony_id: Option<String>,
+    pub credential: Option<PublicKeyCredential>,
+}
@@ -1,3 +1,7 @@
 pub mod user_dto;
 pub mod auth_dto;
 pub mod common_dto;
+pub mod admin_dto;
+pub mod oauth_dto;
+pub mod mfa_dto;
+pub mod webauthn_dto;
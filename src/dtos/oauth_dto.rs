@@ -0,0 +1,104 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::entity::oauth_client;
+use crate::utils::scope;
+
+// Parameter /oauth/authorize. GET membawanya di query string, POST (form consent)
+// membawanya kembali di body bersama keputusan user dan CSRF token.
+#[derive(Deserialize)]
+pub struct AuthorizeRequest {
+    pub response_type: Option<String>,
+    pub client_id: Option<String>,
+    pub redirect_uri: Option<String>,
+    pub scope: Option<String>,
+    pub state: Option<String>,
+    pub code_challenge: Option<String>,
+    pub code_challenge_method: Option<String>,
+    // Hanya di form consent
+    pub decision: Option<String>,
+    pub csrf_token: Option<String>,
+}
+
+// Request /oauth/token (application/x-www-form-urlencoded)
+#[derive(Deserialize)]
+pub struct TokenRequest {
+    pub grant_type: String,
+    pub code: Option<String>,
+    pub redirect_uri: Option<String>,
+    pub code_verifier: Option<String>,
+    pub refresh_token: Option<String>,
+    pub scope: Option<String>,
+    pub client_id: Option<String>,
+    pub client_secret: Option<String>,
+}
+
+// Response sukses /oauth/token (RFC 6749 section 5.1)
+#[derive(Serialize)]
+pub struct TokenResponse {
+    pub access_token: String,
+    pub token_type: String,
+    pub expires_in: i64,
+    // Tidak ada untuk client_credentials
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+    pub scope: String,
+}
+
+// Response error OAuth (RFC 6749 section 5.2)
+#[derive(Serialize)]
+pub struct OAuthErrorResponse {
+    pub error: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_description: Option<String>,
+}
+
+// Request registrasi client OAuth oleh admin
+#[derive(Deserialize)]
+pub struct CreateOAuthClientRequest {
+    pub name: String,
+    // Tidak dipakai oleh service account
+    #[serde(default)]
+    pub redirect_uris: Vec<String>,
+    pub scopes: Vec<String>,
+    // true = client server-side yang bisa menyimpan secret
+    #[serde(default)]
+    pub confidential: bool,
+    // true = service account untuk grant client_credentials (selalu confidential)
+    #[serde(default)]
+    pub service_account: bool,
+}
+
+// Info client OAuth tanpa secret
+#[derive(Serialize)]
+pub struct OAuthClientInfo {
+    pub client_id: String,
+    pub name: String,
+    pub confidential: bool,
+    pub service_account: bool,
+    pub redirect_uris: Vec<String>,
+    pub scopes: Vec<String>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl From<oauth_client::Model> for OAuthClientInfo {
+    fn from(client: oauth_client::Model) -> Self {
+        Self {
+            client_id: client.id,
+            name: client.name,
+            confidential: client.secret_hash.is_some(),
+            service_account: client.service_account,
+            redirect_uris: client.redirect_uris.split_whitespace().map(String::from).collect(),
+            scopes: scope::parse(&client.scopes),
+            created_at: client.created_at,
+        }
+    }
+}
+
+// Response registrasi client, secret hanya ditampilkan sekali ini
+#[derive(Serialize)]
+pub struct CreatedOAuthClientResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_secret: Option<String>,
+    #[serde(flatten)]
+    pub client: OAuthClientInfo,
+}
@@ -0,0 +1,71 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use webauthn_rs::prelude::{PublicKeyCredential, RegisterPublicKeyCredential};
+use crate::entity::webauthn_credential;
+
+// Options untuk navigator.credentials.create() / get(), plus id ceremony
+// yang harus dikirim balik saat finish
+#[derive(Serialize)]
+pub struct WebauthnCeremonyResponse<T> {
+    pub ceremony_id: String,
+    pub options: T,
+}
+
+#[derive(Deserialize)]
+pub struct FinishRegistrationRequest {
+    pub ceremony_id: String,
+    // Label untuk user, mis. "MacBook Touch ID"
+    pub name: Option<String>,
+    pub credential: RegisterPublicKeyCredential,
+}
+
+// Mulai WebAuthn sebagai faktor kedua setelah login mengembalikan challenge MFA
+#[derive(Deserialize)]
+pub struct StartWebauthnMfaRequest {
+    pub challenge_token: String,
+}
+
+#[derive(Deserialize)]
+pub struct FinishWebauthnMfaRequest {
+    pub challenge_token: String,
+    pub ceremony_id: String,
+    pub credential: PublicKeyCredential,
+}
+
+// Login passkey tanpa username
+#[derive(Deserialize)]
+pub struct FinishPasskeyLoginRequest {
+    pub ceremony_id: String,
+    pub credential: PublicKeyCredential,
+    #[serde(default)]
+    pub remember_me: bool,
+}
+
+#[derive(Serialize)]
+pub struct WebauthnCredentialInfo {
+    pub id: i32,
+    pub name: String,
+    pub created_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+// Response registrasi credential. Kode cadangan hanya ikut kalau ini
+// faktor kedua pertama user
+#[derive(Serialize)]
+pub struct RegisteredCredentialResponse {
+    #[serde(flatten)]
+    pub credential: WebauthnCredentialInfo,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recovery_codes: Option<Vec<String>>,
+}
+
+impl From<webauthn_credential::Model> for WebauthnCredentialInfo {
+    fn from(model: webauthn_credential::Model) -> Self {
+        Self {
+            id: model.id,
+            name: model.name,
+            created_at: model.created_at,
+            last_used_at: model.last_used_at,
+        }
+    }
+}
@@ -1 +1,18 @@
-pub mod user;
\ No newline at end of file
+pub mod user;
+pub mod refresh_token;
+pub mod revoked_token;
+pub mod user_session;
+pub mod server_session;
+pub mod impersonation_event;
+pub mod api_key;
+pub mod personal_access_token;
+pub mod oauth_client;
+pub mod oauth_authorization_code;
+pub mod user_identity;
+pub mod magic_link;
+pub mod login_code;
+pub mod user_totp;
+pub mod mfa_challenge;
+pub mod webauthn_credential;
+pub mod webauthn_ceremony;
+pub mod mfa_recovery_code;
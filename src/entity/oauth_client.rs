@@ -0,0 +1,31 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "oauth_clients")]
+pub struct Model {
+    // client_id
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub name: String,
+    // None = public client, wajib PKCE tanpa secret
+    pub secret_hash: Option<String>,
+    // Dipisah spasi
+    pub redirect_uris: String,
+    // Scope maksimal, dipisah spasi
+    pub scopes: String,
+    // true = service account (client_credentials), token-nya tidak mewakili user
+    pub service_account: bool,
+    pub created_at: Option<DateTimeUtc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    pub fn allows_redirect_uri(&self, uri: &str) -> bool {
+        self.redirect_uris.split_whitespace().any(|allowed| allowed == uri)
+    }
+}
@@ -1 +1,18 @@
-pub use super::user::Entity as User;
\ No newline at end of file
+pub use super::user::Entity as User;
+pub use super::refresh_token::Entity as RefreshToken;
+pub use super::revoked_token::Entity as RevokedToken;
+pub use super::user_session::Entity as UserSession;
+pub use super::server_session::Entity as ServerSession;
+pub use super::impersonation_event::Entity as ImpersonationEvent;
+pub use super::api_key::Entity as ApiKey;
+pub use super::personal_access_token::Entity as PersonalAccessToken;
+pub use super::oauth_client::Entity as OAuthClient;
+pub use super::oauth_authorization_code::Entity as OAuthAuthorizationCode;
+pub use super::user_identity::Entity as UserIdentity;
+pub use super::magic_link::Entity as MagicLink;
+pub use super::login_code::Entity as LoginCode;
+pub use super::user_totp::Entity as UserTotp;
+pub use super::mfa_challenge::Entity as MfaChallenge;
+pub use super::webauthn_credential::Entity as WebauthnCredential;
+pub use super::webauthn_ceremony::Entity as WebauthnCeremony;
+pub use super::mfa_recovery_code::Entity as MfaRecoveryCode;
@@ -0,0 +1,46 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "refresh_tokens")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub user_id: i32,
+    #[sea_orm(unique)]
+    pub jti: String,
+    // SHA-256 dari token, token mentah tidak pernah disimpan
+    #[sea_orm(unique)]
+    pub token_hash: String,
+    // Semua token hasil rotasi dari satu login berbagi family_id yang sama
+    pub family_id: String,
+    pub parent_id: Option<i32>,
+    pub expires_at: DateTimeUtc,
+    pub revoked: bool,
+    pub used_at: Option<DateTimeUtc>,
+    // Login dengan "remember me", diwariskan ke token hasil rotasi
+    pub remember_me: bool,
+    // Diisi untuk token dari /oauth/token, tidak bisa dipakai di /api/auth/refresh
+    pub client_id: Option<String>,
+    pub scopes: Option<String>,
+    pub created_at: Option<DateTimeUtc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id",
+        on_delete = "Cascade"
+    )]
+    User,
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,37 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "server_sessions")]
+pub struct Model {
+    // SHA-256 dari session id di cookie, id mentah tidak pernah disimpan
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    // user_sessions.id, supaya session ini ikut muncul di daftar device
+    pub session_id: String,
+    pub user_id: i32,
+    pub email: String,
+    pub role: String,
+    pub token_version: i32,
+    pub expires_at: DateTimeUtc,
+    pub created_at: Option<DateTimeUtc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id",
+        on_delete = "Cascade"
+    )]
+    User,
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -8,8 +8,15 @@ pub struct Model {
     pub id: i32,
     pub username: String,
     pub email: String,
+    // E.164, dipakai untuk login OTP via SMS setelah diverifikasi
+    pub phone: Option<String>,
+    // Kolom generated users.verified_phone (unique, phone kalau sudah diverifikasi) sengaja
+    // tidak dipetakan: nilainya diisi MySQL dan tidak boleh ikut di INSERT/UPDATE
+    pub phone_verified_at: Option<DateTimeUtc>,
     pub password_hash: String,
     pub role: String,
+    // Naik setiap "logout everywhere", token dengan versi lama ditolak
+    pub token_version: i32,
     pub created_at: Option<DateTimeUtc>,
     pub updated_at: Option<DateTimeUtc>,
 }
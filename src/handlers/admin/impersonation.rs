@@ -0,0 +1,144 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use chrono::Duration;
+use sea_orm::*;
+use crate::config::auth;
+use crate::entity::{impersonation_event, user};
+use crate::dtos::admin_dto::ImpersonationResponse;
+use crate::dtos::auth_dto::UserInfo;
+use crate::dtos::common_dto::ApiResponse;
+use crate::middleware::auth_middleware::extract;
+use crate::handlers::user_handler::AppState;
+use crate::utils::jwt::{self, Actor, Claims};
+
+// Catat event impersonation ke tabel audit
+async fn record_event(
+    data: &AppState,
+    req: &HttpRequest,
+    admin_id: i32,
+    target_user_id: i32,
+    jti: &str,
+    action: &str,
+) -> Result<(), DbErr> {
+    let event = impersonation_event::ActiveModel {
+        admin_id: Set(admin_id),
+        target_user_id: Set(target_user_id),
+        jti: Set(jti.to_string()),
+        action: Set(action.to_string()),
+        ip_address: Set(req.connection_info().realip_remote_addr().map(|ip| ip.to_string())),
+        ..Default::default()
+    };
+    event.insert(&data.db).await.map(|_| ())
+}
+
+// Admin: mulai impersonate user lain, dapat access token pendek dengan claim `act`
+pub async fn start_impersonation(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i32>,
+) -> impl Responder {
+    let admin_claims = match extract::require_admin(&req) {
+        Ok(claims) => claims,
+        Err(resp) => return resp,
+    };
+
+    // Tidak boleh impersonate berantai
+    if admin_claims.is_impersonated() {
+        return HttpResponse::Forbidden().json(ApiResponse::<()>::error("Cannot impersonate while impersonating"));
+    }
+
+    let admin_id = match admin_claims.user_id() {
+        Some(id) => id,
+        None => return HttpResponse::Forbidden().json(ApiResponse::<()>::error("Admin access required")),
+    };
+
+    let target_id = path.into_inner();
+    if target_id == admin_id {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error("Cannot impersonate yourself"));
+    }
+
+    let target = match user::Entity::find_by_id(target_id).one(&data.db).await {
+        Ok(Some(user)) => user,
+        Ok(None) => return HttpResponse::NotFound().json(ApiResponse::<()>::error("User not found")),
+        Err(err) => {
+            eprintln!("Database error: {:?}", err);
+            return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"));
+        }
+    };
+
+    // Admin lain tidak boleh di-impersonate
+    if target.role == "admin" {
+        return HttpResponse::Forbidden().json(ApiResponse::<()>::error("Cannot impersonate another admin"));
+    }
+
+    let actor = Actor {
+        sub: admin_claims.sub.clone(),
+        email: Some(admin_claims.email.clone()),
+    };
+    let mut claims = Claims::new_access_token(
+        target.id,
+        target.email.clone(),
+        target.role.clone(),
+        target.token_version,
+        Duration::seconds(auth::impersonation_ttl_secs()),
+    );
+    claims.act = Some(actor.clone());
+
+    let access_token = match jwt::encode_claims(&claims) {
+        Ok(token) => token,
+        Err(err) => {
+            eprintln!("JWT error: {:?}", err);
+            return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Failed to generate token"));
+        }
+    };
+
+    // Tanpa audit trail, token tidak diberikan
+    if let Err(err) = record_event(&data, &req, admin_id, target.id, &claims.jti, "start").await {
+        eprintln!("Database error: {:?}", err);
+        return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Failed to record impersonation"));
+    }
+
+    let response = ImpersonationResponse {
+        access_token,
+        token_type: "Bearer".to_string(),
+        expires_in: claims.lifetime_secs(),
+        user: UserInfo {
+            id: target.id,
+            username: target.username,
+            email: target.email,
+            role: target.role,
+        },
+        impersonator: actor,
+    };
+
+    HttpResponse::Ok().json(ApiResponse::success("Impersonation started", response))
+}
+
+// Akhiri impersonation: dipanggil dengan token impersonation itu sendiri
+pub async fn stop_impersonation(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let claims = match extract::get_claims(&req) {
+        Ok(claims) => claims,
+        Err(resp) => return resp,
+    };
+
+    let (admin_id, target_id) = match (
+        claims.act.as_ref().and_then(|actor| actor.sub.parse::<i32>().ok()),
+        claims.user_id(),
+    ) {
+        (Some(admin_id), Some(target_id)) => (admin_id, target_id),
+        _ => return HttpResponse::BadRequest().json(ApiResponse::<()>::error("Not an impersonation token")),
+    };
+
+    if let Err(err) = data.denylist.revoke(&claims.jti, claims.exp).await {
+        eprintln!("Database error: {:?}", err);
+        return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Failed to revoke token"));
+    }
+
+    if let Err(err) = record_event(&data, &req, admin_id, target_id, &claims.jti, "stop").await {
+        eprintln!("Database error: {:?}", err);
+    }
+
+    HttpResponse::Ok().json(ApiResponse::<()>::success("Impersonation stopped", ()))
+}
@@ -0,0 +1,56 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use chrono::Duration;
+use crate::dtos::admin_dto::{PromoteKeyRequest, SigningKeyInfo};
+use crate::dtos::common_dto::ApiResponse;
+use crate::middleware::auth_middleware::extract;
+use crate::utils::keys;
+
+pub async fn list_keys(req: HttpRequest) -> impl Responder {
+    if let Err(resp) = extract::require_admin(&req) {
+        return resp;
+    }
+
+    let keys: Vec<SigningKeyInfo> = keys::entries().into_iter().map(SigningKeyInfo::from).collect();
+    HttpResponse::Ok().json(ApiResponse::success("Signing keys retrieved", keys))
+}
+
+pub async fn promote_key(
+    req: HttpRequest,
+    path: web::Path<String>,
+    req_body: Option<web::Json<PromoteKeyRequest>>,
+) -> impl Responder {
+    if let Err(resp) = extract::require_admin(&req) {
+        return resp;
+    }
+
+    let grace = req_body
+        .and_then(|body| body.grace_secs)
+        .map(Duration::seconds)
+        .unwrap_or_else(keys::default_grace);
+
+    if grace < Duration::zero() {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error("Grace period cannot be negative"));
+    }
+
+    match keys::promote(&path, grace) {
+        Ok(()) => HttpResponse::Ok().json(ApiResponse::<()>::success("Signing key promoted", ())),
+        Err(err) => {
+            eprintln!("Key promotion error: {}", err);
+            HttpResponse::BadRequest().json(ApiResponse::<()>::error(&err))
+        }
+    }
+}
+
+pub async fn retire_key(req: HttpRequest, path: web::Path<String>) -> impl Responder {
+    if let Err(resp) = extract::require_admin(&req) {
+        return resp;
+    }
+
+    match keys::retire(&path) {
+        Ok(()) => HttpResponse::Ok().json(ApiResponse::<()>::success("Signing key retired", ())),
+        Err(err) => {
+            eprintln!("Key retirement error: {}", err);
+            HttpResponse::BadRequest().json(ApiResponse::<()>::error(&err))
+        }
+    }
+}
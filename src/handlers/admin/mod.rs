@@ -0,0 +1,10 @@
+pub mod impersonation;
+pub mod keys;
+pub mod oauth_clients;
+pub mod users;
+
+// Re-export untuk kemudahan akses
+pub use impersonation::{start_impersonation, stop_impersonation};
+pub use keys::{list_keys, promote_key, retire_key};
+pub use oauth_clients::{list_oauth_clients, create_oauth_client, delete_oauth_client};
+pub use users::{list_user_api_keys, logout_all_user};
@@ -0,0 +1,149 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use chrono::Utc;
+use sea_orm::*;
+use sea_orm::sea_query::Expr;
+use url::Url;
+use crate::entity::{oauth_client, refresh_token};
+use crate::dtos::common_dto::ApiResponse;
+use crate::dtos::oauth_dto::{CreateOAuthClientRequest, CreatedOAuthClientResponse, OAuthClientInfo};
+use crate::middleware::auth_middleware::extract;
+use crate::handlers::user_handler::AppState;
+use crate::utils::{hash, scope};
+
+// Redirect URI harus absolut, tanpa fragment dan userinfo; http hanya untuk host loopback
+// (development). Host dibandingkan persis, jadi http://localhost.evil.com ditolak.
+fn is_valid_redirect_uri(uri: &str) -> bool {
+    if uri.is_empty() || uri.len() > 512 || uri.contains('#') || uri.chars().any(char::is_whitespace) {
+        return false;
+    }
+    let Ok(parsed) = Url::parse(uri) else {
+        return false;
+    };
+    if !parsed.username().is_empty() || parsed.password().is_some() {
+        return false;
+    }
+    match parsed.scheme() {
+        "https" => parsed.host_str().is_some_and(|host| !host.is_empty()),
+        "http" => matches!(parsed.host_str(), Some("localhost" | "127.0.0.1" | "[::1]")),
+        _ => false,
+    }
+}
+
+// Admin: list client OAuth
+pub async fn list_oauth_clients(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    if let Err(resp) = extract::require_admin(&req) {
+        return resp;
+    }
+
+    match oauth_client::Entity::find()
+        .order_by_asc(oauth_client::Column::CreatedAt)
+        .all(&data.db)
+        .await
+    {
+        Ok(clients) => {
+            let clients: Vec<OAuthClientInfo> = clients.into_iter().map(OAuthClientInfo::from).collect();
+            HttpResponse::Ok().json(ApiResponse::success("OAuth clients retrieved", clients))
+        }
+        Err(err) => {
+            eprintln!("Database error: {:?}", err);
+            HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"))
+        }
+    }
+}
+
+// Admin: daftarkan client OAuth baru
+pub async fn create_oauth_client(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    req_body: web::Json<CreateOAuthClientRequest>,
+) -> impl Responder {
+    if let Err(resp) = extract::require_admin(&req) {
+        return resp;
+    }
+
+    // Validasi input
+    let name = req_body.name.trim();
+    if name.is_empty() || name.len() > 100 {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error("Name must be 1-100 characters"));
+    }
+    // Service account tidak pernah lewat /oauth/authorize, jadi tidak butuh redirect URI
+    let redirect_uris_valid = if req_body.service_account {
+        req_body.redirect_uris.is_empty()
+    } else {
+        !req_body.redirect_uris.is_empty() && req_body.redirect_uris.iter().all(|uri| is_valid_redirect_uri(uri))
+    };
+    if !redirect_uris_valid {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error("Invalid redirect_uris"));
+    }
+
+    let scopes = scope::parse(&req_body.scopes.join(" "));
+    if scopes.is_empty() {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error("At least one scope is required"));
+    }
+    if let Some(unknown) = scope::find_unknown(&scopes) {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error(&format!("Unknown scope: {}", unknown)));
+    }
+
+    let confidential = req_body.confidential || req_body.service_account;
+    let client_secret = confidential.then(|| hash::random_token(32));
+    let new_client = oauth_client::ActiveModel {
+        id: Set(hash::random_token(16)),
+        name: Set(name.to_string()),
+        secret_hash: Set(client_secret.as_deref().map(hash::sha256_hex)),
+        redirect_uris: Set(req_body.redirect_uris.join(" ")),
+        scopes: Set(scope::join(&scopes)),
+        service_account: Set(req_body.service_account),
+        created_at: Set(Some(Utc::now())),
+    };
+
+    match new_client.insert(&data.db).await {
+        Ok(model) => {
+            let response = CreatedOAuthClientResponse {
+                client_secret,
+                client: OAuthClientInfo::from(model),
+            };
+            HttpResponse::Created().json(ApiResponse::success("OAuth client created", response))
+        }
+        Err(err) => {
+            eprintln!("Database error: {:?}", err);
+            HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Failed to create OAuth client"))
+        }
+    }
+}
+
+// Admin: hapus client, semua refresh token yang pernah diterbitkan untuknya ikut di-revoke
+pub async fn delete_oauth_client(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+) -> impl Responder {
+    if let Err(resp) = extract::require_admin(&req) {
+        return resp;
+    }
+
+    let client_id = path.into_inner();
+    match oauth_client::Entity::delete_by_id(client_id.clone()).exec(&data.db).await {
+        Ok(result) if result.rows_affected > 0 => {}
+        Ok(_) => return HttpResponse::NotFound().json(ApiResponse::<()>::error("OAuth client not found")),
+        Err(err) => {
+            eprintln!("Database error: {:?}", err);
+            return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"));
+        }
+    }
+
+    match refresh_token::Entity::update_many()
+        .col_expr(refresh_token::Column::Revoked, Expr::value(true))
+        .filter(refresh_token::Column::ClientId.eq(client_id))
+        .exec(&data.db)
+        .await
+    {
+        Ok(_) => HttpResponse::Ok().json(ApiResponse::<()>::success("OAuth client deleted", ())),
+        Err(err) => {
+            eprintln!("Database error: {:?}", err);
+            HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"))
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use sea_orm::*;
+use crate::entity::user;
+use crate::dtos::common_dto::ApiResponse;
+use crate::middleware::auth_middleware::extract;
+use crate::handlers::auth::{api_keys, tokens};
+use crate::handlers::user_handler::AppState;
+
+// Admin: paksa logout user dari semua device (mis. akun terindikasi dibobol)
+pub async fn logout_all_user(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i32>,
+) -> impl Responder {
+    if let Err(resp) = extract::require_admin(&req) {
+        return resp;
+    }
+
+    let user_id = path.into_inner();
+    match user::Entity::find_by_id(user_id).one(&data.db).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return HttpResponse::NotFound().json(ApiResponse::<()>::error("User not found")),
+        Err(err) => {
+            eprintln!("Database error: {:?}", err);
+            return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"));
+        }
+    }
+
+    match tokens::revoke_all_for_user(&data, user_id).await {
+        Ok(()) => HttpResponse::Ok().json(ApiResponse::<()>::success("User logged out from all sessions", ())),
+        Err(err) => {
+            eprintln!("Database error: {:?}", err);
+            HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Failed to revoke tokens"))
+        }
+    }
+}
+
+// Admin: list API key aktif milik user tertentu
+pub async fn list_user_api_keys(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i32>,
+) -> impl Responder {
+    if let Err(resp) = extract::require_admin(&req) {
+        return resp;
+    }
+
+    match api_keys::active_keys_for_user(&data.db, path.into_inner()).await {
+        Ok(keys) => HttpResponse::Ok().json(ApiResponse::success("API keys retrieved", keys)),
+        Err(err) => {
+            eprintln!("Database error: {:?}", err);
+            HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"))
+        }
+    }
+}
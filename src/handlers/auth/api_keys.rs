@@ -0,0 +1,117 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use chrono::{Duration, Utc};
+use sea_orm::*;
+use crate::entity::api_key;
+use crate::dtos::auth_dto::{ApiKeyInfo, CreateApiKeyRequest, CreateApiKeyResponse};
+use crate::dtos::common_dto::ApiResponse;
+use crate::middleware::auth_middleware::extract;
+use crate::handlers::user_handler::AppState;
+use crate::utils::{api_key as api_key_util, hash};
+
+// List API key aktif milik user yang sedang login
+pub async fn list_api_keys(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let (_, user_id) = match extract::require_user(&req) {
+        Ok(found) => found,
+        Err(resp) => return resp,
+    };
+
+    match active_keys_for_user(&data.db, user_id).await {
+        Ok(keys) => HttpResponse::Ok().json(ApiResponse::success("API keys retrieved", keys)),
+        Err(err) => {
+            eprintln!("Database error: {:?}", err);
+            HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"))
+        }
+    }
+}
+
+// Dipakai juga oleh endpoint admin
+pub async fn active_keys_for_user(db: &DatabaseConnection, user_id: i32) -> Result<Vec<ApiKeyInfo>, DbErr> {
+    let keys = api_key::Entity::find()
+        .filter(api_key::Column::UserId.eq(user_id))
+        .filter(api_key::Column::RevokedAt.is_null())
+        .order_by_desc(api_key::Column::CreatedAt)
+        .all(db)
+        .await?;
+    Ok(keys.into_iter().map(ApiKeyInfo::from).collect())
+}
+
+// Buat API key baru untuk user yang sedang login
+pub async fn create_api_key(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    req_body: web::Json<CreateApiKeyRequest>,
+) -> impl Responder {
+    // API key, token ber-scope dan sesi impersonation tidak boleh menerbitkan API key
+    let (_, user_id) = match extract::require_interactive_user(&req) {
+        Ok(found) => found,
+        Err(resp) => return resp,
+    };
+
+    // Validasi input
+    let name = req_body.name.trim();
+    if name.is_empty() || name.len() > 100 {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error("Name must be 1-100 characters"));
+    }
+    if req_body.expires_in_days.is_some_and(|days| days <= 0) {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error("expires_in_days must be positive"));
+    }
+
+    let (key, prefix) = api_key_util::generate();
+    let new_key = api_key::ActiveModel {
+        user_id: Set(user_id),
+        name: Set(name.to_string()),
+        prefix: Set(prefix),
+        key_hash: Set(hash::sha256_hex(&key)),
+        expires_at: Set(req_body.expires_in_days.map(|days| Utc::now() + Duration::days(days))),
+        ..Default::default()
+    };
+
+    match new_key.insert(&data.db).await {
+        Ok(model) => {
+            let response = CreateApiKeyResponse {
+                key,
+                info: ApiKeyInfo::from(model),
+            };
+            HttpResponse::Created().json(ApiResponse::success("API key created", response))
+        }
+        Err(err) => {
+            eprintln!("Database error: {:?}", err);
+            HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Failed to create API key"))
+        }
+    }
+}
+
+// Revoke API key milik sendiri, admin boleh revoke key siapa saja
+pub async fn revoke_api_key(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i32>,
+) -> impl Responder {
+    let (_, user_id) = match extract::require_user(&req) {
+        Ok(found) => found,
+        Err(resp) => return resp,
+    };
+
+    let mut query = api_key::Entity::update_many()
+        .col_expr(api_key::Column::RevokedAt, sea_query::Expr::value(Utc::now()))
+        .filter(api_key::Column::Id.eq(path.into_inner()))
+        .filter(api_key::Column::RevokedAt.is_null());
+    // Key milik user lain dianggap tidak ada, kecuali untuk admin (termasuk cek scope "admin")
+    if extract::require_admin(&req).is_err() {
+        query = query.filter(api_key::Column::UserId.eq(user_id));
+    }
+
+    match query.exec(&data.db).await {
+        Ok(result) if result.rows_affected > 0 => {
+            HttpResponse::Ok().json(ApiResponse::<()>::success("API key revoked", ()))
+        }
+        Ok(_) => HttpResponse::NotFound().json(ApiResponse::<()>::error("API key not found")),
+        Err(err) => {
+            eprintln!("Database error: {:?}", err);
+            HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"))
+        }
+    }
+}
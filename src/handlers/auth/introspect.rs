@@ -0,0 +1,155 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use sea_orm::*;
+use std::env;
+use crate::entity::{oauth_client, refresh_token};
+use crate::dtos::auth_dto::{IntrospectionRequest, IntrospectionResponse};
+use crate::dtos::common_dto::ApiResponse;
+use crate::utils::{hash, jwt, scope};
+use crate::utils::jwt::Principal;
+use crate::handlers::user_handler::AppState;
+
+// Token introspection (RFC 7662) untuk service internal.
+// Caller harus autentikasi dengan salah satu:
+//   - header X-Internal-Api-Key yang cocok dengan INTROSPECTION_API_KEY
+//   - HTTP Basic client_id:secret yang terdaftar di INTROSPECTION_CLIENTS
+//     (format "client_a:secret_a,client_b:secret_b")
+pub async fn introspect(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    form: web::Form<IntrospectionRequest>,
+) -> impl Responder {
+    if !authenticate_caller(&req) {
+        return HttpResponse::Unauthorized()
+            .insert_header(("WWW-Authenticate", "Basic realm=\"introspection\""))
+            .json(ApiResponse::<()>::error("Invalid client credentials"));
+    }
+
+    let inactive = || {
+        HttpResponse::Ok()
+            .insert_header(("Cache-Control", "no-store"))
+            .json(IntrospectionResponse::default())
+    };
+
+    // Token yang invalid, expired atau sudah di-revoke cukup dijawab active=false
+    let claims = match jwt::validate_token(&form.token) {
+        Ok(claims) => claims,
+        Err(_) => return inactive(),
+    };
+
+    match data.denylist.is_revoked(&claims.jti).await {
+        Ok(false) => {}
+        Ok(true) => return inactive(),
+        Err(err) => {
+            eprintln!("Database error: {:?}", err);
+            return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"));
+        }
+    }
+
+    match claims.principal() {
+        // Sama seperti JwtMiddleware: token yang terbit sebelum "logout everywhere"
+        // atau ganti password sudah tidak berlaku
+        Some(Principal::User(user_id)) => {
+            match data.token_versions.current(&data.db, user_id).await {
+                Ok(Some(version)) if version == claims.ver => {}
+                Ok(_) => return inactive(),
+                Err(err) => {
+                    eprintln!("Token version lookup error: {:?}", err);
+                    return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"));
+                }
+            }
+        }
+        // Token service account mati begitu client-nya dihapus
+        Some(Principal::Client(client_id)) => {
+            match oauth_client::Entity::find_by_id(client_id).one(&data.db).await {
+                Ok(Some(client)) if client.service_account => {}
+                Ok(_) => return inactive(),
+                Err(err) => {
+                    eprintln!("Client lookup error: {:?}", err);
+                    return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"));
+                }
+            }
+        }
+        None => return inactive(),
+    }
+
+    // Token dari session yang sudah di-revoke
+    if let Some(sid) = &claims.sid {
+        match data.revoked_sessions.is_revoked(&data.db, sid).await {
+            Ok(false) => {}
+            Ok(true) => return inactive(),
+            Err(err) => {
+                eprintln!("Session lookup error: {:?}", err);
+                return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"));
+            }
+        }
+    }
+
+    // Refresh token hanya aktif selama belum dirotasi atau di-revoke
+    if claims.token_type == "refresh" {
+        match refresh_token::Entity::find()
+            .filter(refresh_token::Column::TokenHash.eq(hash::sha256_hex(&form.token)))
+            .one(&data.db)
+            .await
+        {
+            Ok(Some(stored)) if !stored.revoked && stored.used_at.is_none() => {}
+            Ok(_) => return inactive(),
+            Err(err) => {
+                eprintln!("Database error: {:?}", err);
+                return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"));
+            }
+        }
+    }
+
+    // Token service account tidak punya username
+    let username = claims.user_id().map(|_| claims.email.clone());
+
+    let response = IntrospectionResponse {
+        active: true,
+        scope: claims.scopes.as_deref().map(scope::join),
+        client_id: claims.client_id,
+        username,
+        token_type: Some(claims.token_type),
+        exp: Some(claims.exp),
+        iat: Some(claims.iat),
+        nbf: Some(claims.nbf),
+        sub: Some(claims.sub),
+        aud: Some(claims.aud),
+        iss: Some(claims.iss),
+        jti: Some(claims.jti),
+        role: Some(claims.role),
+    };
+
+    HttpResponse::Ok()
+        .insert_header(("Cache-Control", "no-store"))
+        .json(response)
+}
+
+fn authenticate_caller(req: &HttpRequest) -> bool {
+    if let Some(key) = req.headers().get("X-Internal-Api-Key").and_then(|h| h.to_str().ok()) {
+        return env::var("INTROSPECTION_API_KEY")
+            .map(|expected| !expected.is_empty() && hash::constant_time_eq(key, &expected))
+            .unwrap_or(false);
+    }
+
+    let credentials = req
+        .headers()
+        .get("Authorization")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.strip_prefix("Basic "))
+        .and_then(|encoded| STANDARD.decode(encoded).ok())
+        .and_then(|decoded| String::from_utf8(decoded).ok());
+
+    let Some(credentials) = credentials else {
+        return false;
+    };
+    let Some((client_id, secret)) = credentials.split_once(':') else {
+        return false;
+    };
+
+    let clients = env::var("INTROSPECTION_CLIENTS").unwrap_or_default();
+    clients
+        .split(',')
+        .filter_map(|pair| pair.trim().split_once(':'))
+        .any(|(id, expected)| id == client_id && hash::constant_time_eq(secret, expected))
+}
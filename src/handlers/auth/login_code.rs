@@ -0,0 +1,236 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use chrono::{Duration, Utc};
+use sea_orm::*;
+use sea_orm::sea_query::Expr;
+use crate::config::auth;
+use crate::entity::{login_code, user};
+use crate::dtos::auth_dto::{LoginCodeRequest, LoginCodeVerifyRequest};
+use crate::dtos::common_dto::ApiResponse;
+use crate::handlers::user_handler::AppState;
+use crate::utils::{hash, otp};
+use super::mfa;
+
+// Salah tebak lebih dari ini, kode hangus dan user harus minta kode baru
+const MAX_ATTEMPTS: i32 = 5;
+
+// Jeda minimal antar pengiriman kode untuk user + channel yang sama
+const RESEND_COOLDOWN_SECS: i64 = 60;
+
+// Response selalu sama, supaya endpoint ini tidak bisa dipakai untuk cek akun terdaftar
+const SENT_MESSAGE: &str = "If an account matches, a sign-in code has been sent";
+
+// Tujuan pengiriman dari request: (channel, email / nomor E.164)
+fn parse_destination(email: Option<&str>, phone: Option<&str>) -> Result<(&'static str, String), HttpResponse> {
+    let email = email.map(str::trim).filter(|v| !v.is_empty());
+    let phone = phone.map(str::trim).filter(|v| !v.is_empty());
+
+    match (email, phone) {
+        (Some(email), None) if email.contains('@') => Ok((otp::CHANNEL_EMAIL, email.to_lowercase())),
+        (Some(_), None) => Err(HttpResponse::BadRequest().json(ApiResponse::<()>::error("Invalid email format"))),
+        (None, Some(phone)) => match otp::normalize_phone(phone) {
+            Some(phone) => Ok((otp::CHANNEL_SMS, phone)),
+            None => Err(HttpResponse::BadRequest().json(ApiResponse::<()>::error("Phone number must be in international format, e.g. +628123456789"))),
+        },
+        _ => Err(HttpResponse::BadRequest().json(ApiResponse::<()>::error("Provide either email or phone"))),
+    }
+}
+
+// Nomor telepon hanya dipakai sebagai identitas login setelah diverifikasi lewat SMS
+async fn find_user(db: &DatabaseConnection, channel: &str, destination: &str) -> Result<Option<user::Model>, DbErr> {
+    let query = if channel == otp::CHANNEL_SMS {
+        user::Entity::find()
+            .filter(user::Column::Phone.eq(destination))
+            .filter(user::Column::PhoneVerifiedAt.is_not_null())
+    } else {
+        user::Entity::find().filter(user::Column::Email.eq(destination))
+    };
+    query.one(db).await
+}
+
+// POST /api/auth/otp: kirim kode 6 digit ke email atau nomor telepon user
+pub async fn request_login_code(
+    data: web::Data<AppState>,
+    req_body: web::Json<LoginCodeRequest>,
+) -> impl Responder {
+    let (channel, destination) = match parse_destination(req_body.email.as_deref(), req_body.phone.as_deref()) {
+        Ok(found) => found,
+        Err(resp) => return resp,
+    };
+
+    let user_model = match find_user(&data.db, channel, &destination).await {
+        Ok(Some(user_model)) => user_model,
+        Ok(None) => return HttpResponse::Ok().json(ApiResponse::<()>::success(SENT_MESSAGE, ())),
+        Err(err) => {
+            eprintln!("Database error: {:?}", err);
+            return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"));
+        }
+    };
+
+    if let Err(resp) = send_code(&data, user_model.id, channel, channel, &destination).await {
+        return resp;
+    }
+
+    HttpResponse::Ok().json(ApiResponse::<()>::success(SENT_MESSAGE, ()))
+}
+
+// Simpan kode baru untuk user + `purpose` lalu kirim lewat channel `delivery`.
+// Ok(false) = masih dalam cooldown, tidak ada kode yang dikirim.
+// `purpose` biasanya sama dengan channel; verifikasi nomor telepon memakai purpose sendiri
+// supaya kodenya tidak bisa dipakai untuk login.
+pub(crate) async fn send_code(
+    data: &AppState,
+    user_id: i32,
+    purpose: &str,
+    delivery: &str,
+    destination: &str,
+) -> Result<bool, HttpResponse> {
+    let db_error = |err: DbErr| {
+        eprintln!("Database error: {:?}", err);
+        HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"))
+    };
+
+    let now = Utc::now();
+
+    // Jangan banjiri inbox / SMS user: abaikan request baru selama cooldown
+    let recent = login_code::Entity::find()
+        .filter(login_code::Column::UserId.eq(user_id))
+        .filter(login_code::Column::Channel.eq(purpose))
+        .filter(login_code::Column::CreatedAt.gt(now - Duration::seconds(RESEND_COOLDOWN_SECS)))
+        .one(&data.db)
+        .await
+        .map_err(db_error)?;
+    if recent.is_some() {
+        return Ok(false);
+    }
+
+    // Hanya kode terbaru untuk purpose ini yang berlaku; purpose lain tidak disentuh supaya
+    // request login tanpa autentikasi tidak bisa membatalkan verifikasi nomor telepon
+    login_code::Entity::update_many()
+        .col_expr(login_code::Column::ConsumedAt, Expr::value(now))
+        .filter(login_code::Column::UserId.eq(user_id))
+        .filter(login_code::Column::Channel.eq(purpose))
+        .filter(login_code::Column::ConsumedAt.is_null())
+        .exec(&data.db)
+        .await
+        .map_err(db_error)?;
+
+    // Kode 6 digit mudah di-brute force kalau hanya SHA-256, jadi pakai bcrypt
+    let code = otp::generate_code();
+    let code_hash = hash::hash_password(&code).map_err(|err| {
+        eprintln!("Hash error: {:?}", err);
+        HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Failed to generate code"))
+    })?;
+
+    let ttl_secs = auth::otp_ttl_secs();
+    login_code::ActiveModel {
+        user_id: Set(user_id),
+        channel: Set(purpose.to_string()),
+        code_hash: Set(code_hash),
+        expires_at: Set(now + Duration::seconds(ttl_secs)),
+        ..Default::default()
+    }
+    .insert(&data.db)
+    .await
+    .map_err(db_error)?;
+
+    // Gagal kirim hanya dicatat, response tetap sama
+    if let Err(err) = data.otp_channels.for_channel(delivery).deliver(destination, &code, ttl_secs).await {
+        eprintln!("OTP delivery error ({}): {}", delivery, err);
+    }
+
+    Ok(true)
+}
+
+// Cek kode terbaru milik user + `purpose`. Percobaan dihitung sebelum verifikasi dan kode
+// ditandai terpakai secara atomik, jadi request paralel tidak melewati batas dan kode yang
+// sama hanya lolos sekali.
+pub(crate) async fn check_code(
+    data: &AppState,
+    user_id: i32,
+    purpose: &str,
+    code: &str,
+) -> Result<bool, HttpResponse> {
+    let db_error = |err: DbErr| {
+        eprintln!("Database error: {:?}", err);
+        HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"))
+    };
+
+    let code = code.trim();
+    if code.len() != 6 || !code.chars().all(|c| c.is_ascii_digit()) {
+        return Ok(false);
+    }
+
+    let now = Utc::now();
+    let pending = login_code::Entity::find()
+        .filter(login_code::Column::UserId.eq(user_id))
+        .filter(login_code::Column::Channel.eq(purpose))
+        .filter(login_code::Column::ConsumedAt.is_null())
+        .filter(login_code::Column::ExpiresAt.gt(now))
+        .order_by_desc(login_code::Column::Id)
+        .one(&data.db)
+        .await
+        .map_err(db_error)?;
+    let Some(pending) = pending else {
+        return Ok(false);
+    };
+
+    let counted = login_code::Entity::update_many()
+        .col_expr(login_code::Column::Attempts, Expr::col(login_code::Column::Attempts).add(1))
+        .filter(login_code::Column::Id.eq(pending.id))
+        .filter(login_code::Column::Attempts.lt(MAX_ATTEMPTS))
+        .filter(login_code::Column::ConsumedAt.is_null())
+        .exec(&data.db)
+        .await
+        .map_err(db_error)?;
+    if counted.rows_affected != 1 {
+        return Err(HttpResponse::TooManyRequests().json(ApiResponse::<()>::error("Too many attempts, please request a new code")));
+    }
+
+    match hash::verify_password(code, &pending.code_hash) {
+        Ok(true) => {}
+        Ok(false) => return Ok(false),
+        Err(err) => {
+            eprintln!("Password verification error: {:?}", err);
+            return Err(HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Authentication error")));
+        }
+    }
+
+    let consumed = login_code::Entity::update_many()
+        .col_expr(login_code::Column::ConsumedAt, Expr::value(now))
+        .filter(login_code::Column::Id.eq(pending.id))
+        .filter(login_code::Column::ConsumedAt.is_null())
+        .exec(&data.db)
+        .await
+        .map_err(db_error)?;
+
+    Ok(consumed.rows_affected == 1)
+}
+
+// POST /api/auth/otp/verify: tukar kode dengan access & refresh token
+pub async fn verify_login_code(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    req_body: web::Json<LoginCodeVerifyRequest>,
+) -> impl Responder {
+    let invalid = || HttpResponse::Unauthorized().json(ApiResponse::<()>::error("Invalid or expired code"));
+
+    let (channel, destination) = match parse_destination(req_body.email.as_deref(), req_body.phone.as_deref()) {
+        Ok(found) => found,
+        Err(resp) => return resp,
+    };
+
+    let user_model = match find_user(&data.db, channel, &destination).await {
+        Ok(Some(user_model)) => user_model,
+        Ok(None) => return invalid(),
+        Err(err) => {
+            eprintln!("Database error: {:?}", err);
+            return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"));
+        }
+    };
+
+    match check_code(&data, user_model.id, channel, &req_body.code).await {
+        Ok(true) => mfa::complete_login(&data, &req, user_model, req_body.remember_me).await,
+        Ok(false) => invalid(),
+        Err(resp) => resp,
+    }
+}
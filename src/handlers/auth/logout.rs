@@ -1,22 +1,94 @@
-use actix_web::{HttpResponse, Responder, cookie::{Cookie, time::Duration as CookieDuration}};
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use crate::config::cookie::{self, REFRESH_TOKEN, SESSION_ID};
+use crate::dtos::auth_dto::RefreshRequest;
 use crate::dtos::common_dto::ApiResponse;
+use crate::middleware::auth_middleware::{extract, extract_access_token, extract_credential, Credential};
+use crate::middleware::csrf;
+use crate::utils::{hash, jwt};
+use crate::handlers::user_handler::AppState;
+use super::tokens;
+
+pub async fn logout(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    req_body: Option<web::Json<RefreshRequest>>,
+) -> impl Responder {
+    // Logout lewat cookie juga butuh CSRF token, supaya situs lain tidak bisa
+    // me-logout user secara paksa
+    let has_auth_cookie = [cookie::ACCESS_TOKEN, REFRESH_TOKEN, SESSION_ID]
+        .iter()
+        .any(|name| cookie::get(&req, name).is_some());
+    let from_cookie = has_auth_cookie && !matches!(extract_credential(&req), Some(Credential::Bearer(_)));
+    if from_cookie && !csrf::verify(&req) {
+        return HttpResponse::Forbidden().json(ApiResponse::<()>::error("Invalid or missing CSRF token"));
+    }
+
+    let refresh = req_body
+        .and_then(|body| body.into_inner().refresh_token)
+        .or_else(|| cookie::get(&req, REFRESH_TOKEN));
+
+    // Masukkan jti access & refresh token ke denylist sampai exp masing-masing.
+    // Token yang sudah invalid/expired tidak perlu di-revoke.
+    let presented = [extract_access_token(&req), refresh];
+    for token in presented.iter().flatten() {
+        let Ok(claims) = jwt::validate_token(token) else {
+            continue;
+        };
+
+        if let Err(err) = data.denylist.revoke(&claims.jti, claims.exp).await {
+            eprintln!("Database error: {:?}", err);
+            return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Failed to revoke token"));
+        }
+
+        // Matikan juga session-nya (refresh token family), supaya hasil rotasi lain ikut mati.
+        // Refresh cookie hanya dikirim ke path refresh, jadi sid dari access token yang biasanya dipakai.
+        if let Some(sid) = &claims.sid
+            && let Err(err) = tokens::revoke_family(&data, sid).await
+        {
+            eprintln!("Database error: {:?}", err);
+        }
+    }
+
+    // Mode session: hapus session server-side beserta entry device-nya
+    if let Some(session_id) = cookie::get(&req, SESSION_ID) {
+        match data.sessions.get(&hash::sha256_hex(&session_id)).await {
+            Ok(Some(session)) => {
+                if let Err(err) = tokens::revoke_family(&data, &session.session_id).await {
+                    eprintln!("Database error: {:?}", err);
+                    return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Failed to revoke session"));
+                }
+            }
+            Ok(None) => {}
+            Err(err) => eprintln!("Database error: {:?}", err),
+        }
+    }
 
-pub async fn logout() -> impl Responder {
     // Clear cookies dengan set max_age ke 0
-    let access_cookie = Cookie::build("access_token", "")
-        .path("/")
-        .http_only(true)
-        .max_age(CookieDuration::ZERO)
-        .finish();
-
-    let refresh_cookie = Cookie::build("refresh_token", "")
-        .path("/")
-        .http_only(true)
-        .max_age(CookieDuration::ZERO)
-        .finish();
-
-    HttpResponse::Ok()
-        .cookie(access_cookie)
-        .cookie(refresh_cookie)
-        .json(ApiResponse::<()>::success("Logout successful", ()))
+    let mut response = HttpResponse::Ok();
+    for cookie in cookie::policy().clear_all() {
+        response.cookie(cookie);
+    }
+    response.json(ApiResponse::<()>::success("Logout successful", ()))
+}
+
+// Logout dari semua device: semua token milik user yang sudah terbit jadi tidak valid
+pub async fn logout_all(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let (_, user_id) = match extract::require_user(&req) {
+        Ok(found) => found,
+        Err(resp) => return resp,
+    };
+
+    if let Err(err) = tokens::revoke_all_for_user(&data, user_id).await {
+        eprintln!("Database error: {:?}", err);
+        return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Failed to revoke tokens"));
+    }
+
+    let mut response = HttpResponse::Ok();
+    for cookie in cookie::policy().clear_all() {
+        response.cookie(cookie);
+    }
+    response.json(ApiResponse::<()>::success("Logged out from all sessions", ()))
 }
@@ -0,0 +1,165 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use chrono::{Duration, Utc};
+use sea_orm::*;
+use sea_orm::sea_query::Expr;
+use crate::config::auth;
+use crate::entity::{magic_link, user};
+use crate::dtos::auth_dto::{MagicLinkRequest, MagicLinkVerifyRequest};
+use crate::dtos::common_dto::ApiResponse;
+use crate::handlers::user_handler::AppState;
+use crate::utils::hash;
+use crate::utils::mailer::Email;
+use super::mfa;
+
+// Jeda minimal antar email magic link untuk user yang sama
+const RESEND_COOLDOWN_SECS: i64 = 60;
+
+// Response selalu sama, supaya endpoint ini tidak bisa dipakai untuk cek email terdaftar
+const SENT_MESSAGE: &str = "If the email is registered, a sign-in link has been sent";
+
+// POST /api/auth/magic-link: kirim link login sekali pakai ke email user
+pub async fn request_magic_link(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    req_body: web::Json<MagicLinkRequest>,
+) -> impl Responder {
+    let email = req_body.email.trim().to_lowercase();
+    if email.is_empty() || !email.contains('@') {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error("Invalid email format"));
+    }
+
+    let user_model = match user::Entity::find()
+        .filter(user::Column::Email.eq(&email))
+        .one(&data.db)
+        .await
+    {
+        Ok(Some(user_model)) => user_model,
+        Ok(None) => return HttpResponse::Ok().json(ApiResponse::<()>::success(SENT_MESSAGE, ())),
+        Err(err) => {
+            eprintln!("Database error: {:?}", err);
+            return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"));
+        }
+    };
+
+    let now = Utc::now();
+
+    // Jangan banjiri inbox user: abaikan request baru selama cooldown
+    match magic_link::Entity::find()
+        .filter(magic_link::Column::UserId.eq(user_model.id))
+        .filter(magic_link::Column::UsedAt.is_null())
+        .filter(magic_link::Column::CreatedAt.gt(now - Duration::seconds(RESEND_COOLDOWN_SECS)))
+        .one(&data.db)
+        .await
+    {
+        Ok(Some(_)) => return HttpResponse::Ok().json(ApiResponse::<()>::success(SENT_MESSAGE, ())),
+        Ok(None) => {}
+        Err(err) => {
+            eprintln!("Database error: {:?}", err);
+            return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"));
+        }
+    }
+
+    // Hanya link terbaru yang berlaku
+    if let Err(err) = magic_link::Entity::update_many()
+        .col_expr(magic_link::Column::UsedAt, Expr::value(now))
+        .filter(magic_link::Column::UserId.eq(user_model.id))
+        .filter(magic_link::Column::UsedAt.is_null())
+        .exec(&data.db)
+        .await
+    {
+        eprintln!("Database error: {:?}", err);
+        return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"));
+    }
+
+    let token = hash::random_token(32);
+    let ttl_secs = auth::magic_link_ttl_secs();
+    let new_link = magic_link::ActiveModel {
+        user_id: Set(user_model.id),
+        token_hash: Set(hash::sha256_hex(&token)),
+        remember_me: Set(req_body.remember_me),
+        ip_address: Set(req.connection_info().realip_remote_addr().map(|ip| ip.to_string())),
+        expires_at: Set(now + Duration::seconds(ttl_secs)),
+        ..Default::default()
+    };
+
+    if let Err(err) = new_link.insert(&data.db).await {
+        eprintln!("Database error: {:?}", err);
+        return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"));
+    }
+
+    let base_url = auth::magic_link_url();
+    let separator = if base_url.contains('?') { '&' } else { '?' };
+    let link = format!("{}{}token={}", base_url, separator, token);
+    let email = Email {
+        to: user_model.email.clone(),
+        subject: "Your sign-in link".to_string(),
+        body: format!(
+            "Hi {},\n\nUse the link below to sign in. It expires in {} minutes and can only be used once.\n\n{}\n\n\
+             If you did not request this, you can ignore this email.",
+            user_model.username,
+            ttl_secs / 60,
+            link
+        ),
+    };
+
+    // Gagal kirim hanya dicatat, response tetap sama
+    if let Err(err) = data.mailer.send(&email).await {
+        eprintln!("Mail error: {}", err);
+    }
+
+    HttpResponse::Ok().json(ApiResponse::<()>::success(SENT_MESSAGE, ()))
+}
+
+// POST /api/auth/magic-link/verify: tukar token dari link dengan token login
+pub async fn verify_magic_link(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    req_body: web::Json<MagicLinkVerifyRequest>,
+) -> impl Responder {
+    let invalid = || HttpResponse::Unauthorized().json(ApiResponse::<()>::error("Invalid or expired sign-in link"));
+
+    if req_body.token.trim().is_empty() {
+        return invalid();
+    }
+
+    let now = Utc::now();
+    let link = match magic_link::Entity::find()
+        .filter(magic_link::Column::TokenHash.eq(hash::sha256_hex(req_body.token.trim())))
+        .one(&data.db)
+        .await
+    {
+        Ok(Some(link)) if link.used_at.is_none() && link.expires_at > now => link,
+        Ok(_) => return invalid(),
+        Err(err) => {
+            eprintln!("Database error: {:?}", err);
+            return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"));
+        }
+    };
+
+    // Tandai terpakai secara atomik, request paralel dengan token yang sama hanya satu yang lolos
+    match magic_link::Entity::update_many()
+        .col_expr(magic_link::Column::UsedAt, Expr::value(now))
+        .filter(magic_link::Column::Id.eq(link.id))
+        .filter(magic_link::Column::UsedAt.is_null())
+        .exec(&data.db)
+        .await
+    {
+        Ok(result) if result.rows_affected == 1 => {}
+        Ok(_) => return invalid(),
+        Err(err) => {
+            eprintln!("Database error: {:?}", err);
+            return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"));
+        }
+    }
+
+    match user::Entity::find_by_id(link.user_id).one(&data.db).await {
+        Ok(Some(user_model)) => {
+            mfa::complete_login(&data, &req, user_model, link.remember_me).await
+        }
+        Ok(None) => invalid(),
+        Err(err) => {
+            eprintln!("Database error: {:?}", err);
+            HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"))
+        }
+    }
+}
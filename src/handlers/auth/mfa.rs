@@ -0,0 +1,228 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use chrono::{Duration, Utc};
+use sea_orm::*;
+use sea_orm::sea_query::Expr;
+use crate::config::auth;
+use crate::entity::{mfa_challenge, user};
+use crate::dtos::common_dto::ApiResponse;
+use crate::dtos::mfa_dto::{MfaChallengeResponse, MfaStatusResponse, MfaVerifyRequest, SecondFactorProof};
+use crate::middleware::auth_middleware::extract;
+use crate::handlers::user_handler::AppState;
+use crate::utils::hash;
+use super::{recovery_codes, tokens, totp, webauthn};
+
+// Salah kode lebih dari ini, challenge hangus dan user harus login ulang
+const MAX_ATTEMPTS: i32 = 5;
+
+pub const METHOD_TOTP: &str = "totp";
+pub const METHOD_WEBAUTHN: &str = "webauthn";
+pub const METHOD_RECOVERY_CODE: &str = "recovery_code";
+
+// Faktor kedua yang aktif untuk user, kosong = tanpa MFA
+async fn enabled_methods(db: &DatabaseConnection, user_id: i32) -> Result<Vec<String>, DbErr> {
+    let mut methods = Vec::new();
+    if totp::is_enabled(db, user_id).await? {
+        methods.push(METHOD_TOTP.to_string());
+    }
+    if webauthn::has_credentials(db, user_id).await? {
+        methods.push(METHOD_WEBAUTHN.to_string());
+    }
+    // Kode cadangan hanya pengganti faktor lain, bukan faktor tersendiri
+    if !methods.is_empty() && recovery_codes::remaining(db, user_id).await? > 0 {
+        methods.push(METHOD_RECOVERY_CODE.to_string());
+    }
+    Ok(methods)
+}
+
+// Akhir dari setiap login faktor pertama (password, magic link, OTP, OIDC).
+// User tanpa MFA langsung mendapat token; user dengan MFA mendapat challenge token
+// yang harus ditukar di POST /api/auth/mfa/verify.
+pub async fn complete_login(
+    data: &AppState,
+    req: &HttpRequest,
+    user_model: user::Model,
+    remember_me: bool,
+) -> HttpResponse {
+    let methods = match enabled_methods(&data.db, user_model.id).await {
+        Ok(methods) => methods,
+        Err(err) => {
+            eprintln!("Database error: {:?}", err);
+            return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"));
+        }
+    };
+
+    if methods.is_empty() {
+        return tokens::issue_tokens(data, req, user_model, None, remember_me, "Login successful").await;
+    }
+
+    let challenge_token = hash::random_token(32);
+    let ttl_secs = auth::mfa_challenge_ttl_secs();
+    let challenge = mfa_challenge::ActiveModel {
+        user_id: Set(user_model.id),
+        token_hash: Set(hash::sha256_hex(&challenge_token)),
+        remember_me: Set(remember_me),
+        expires_at: Set(Utc::now() + Duration::seconds(ttl_secs)),
+        ..Default::default()
+    };
+
+    if let Err(err) = challenge.insert(&data.db).await {
+        eprintln!("Database error: {:?}", err);
+        return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"));
+    }
+
+    let response = MfaChallengeResponse {
+        mfa_required: true,
+        challenge_token,
+        methods,
+        expires_in: ttl_secs,
+    };
+    HttpResponse::Ok().json(ApiResponse::success("Multi-factor authentication required", response))
+}
+
+// Challenge yang masih berlaku untuk token ini
+pub(crate) async fn find_active(db: &DatabaseConnection, challenge_token: &str) -> Result<mfa_challenge::Model, HttpResponse> {
+    match mfa_challenge::Entity::find()
+        .filter(mfa_challenge::Column::TokenHash.eq(hash::sha256_hex(challenge_token.trim())))
+        .one(db)
+        .await
+    {
+        Ok(Some(challenge)) if challenge.consumed_at.is_none() && challenge.expires_at > Utc::now() => Ok(challenge),
+        Ok(_) => Err(HttpResponse::Unauthorized().json(ApiResponse::<()>::error("Invalid or expired MFA challenge"))),
+        Err(err) => {
+            eprintln!("Database error: {:?}", err);
+            Err(HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error")))
+        }
+    }
+}
+
+// Ambil challenge yang masih berlaku dan hitung satu percobaan.
+// Percobaan dihitung sebelum faktor kedua dicek, secara atomik supaya request paralel tidak melewati batas.
+pub(crate) async fn start_attempt(db: &DatabaseConnection, challenge_token: &str) -> Result<mfa_challenge::Model, HttpResponse> {
+    let challenge = find_active(db, challenge_token).await?;
+
+    let result = mfa_challenge::Entity::update_many()
+        .col_expr(mfa_challenge::Column::Attempts, Expr::col(mfa_challenge::Column::Attempts).add(1))
+        .filter(mfa_challenge::Column::Id.eq(challenge.id))
+        .filter(mfa_challenge::Column::Attempts.lt(MAX_ATTEMPTS))
+        .filter(mfa_challenge::Column::ConsumedAt.is_null())
+        .exec(db)
+        .await
+        .map_err(|err| {
+            eprintln!("Database error: {:?}", err);
+            HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"))
+        })?;
+
+    if result.rows_affected != 1 {
+        return Err(HttpResponse::TooManyRequests().json(ApiResponse::<()>::error("Too many attempts, please sign in again")));
+    }
+
+    Ok(challenge)
+}
+
+// Faktor kedua valid: habiskan challenge lalu terbitkan token
+pub(crate) async fn finish(data: &AppState, req: &HttpRequest, challenge: mfa_challenge::Model) -> HttpResponse {
+    let consumed = mfa_challenge::Entity::update_many()
+        .col_expr(mfa_challenge::Column::ConsumedAt, Expr::value(Utc::now()))
+        .filter(mfa_challenge::Column::Id.eq(challenge.id))
+        .filter(mfa_challenge::Column::ConsumedAt.is_null())
+        .exec(&data.db)
+        .await;
+
+    match consumed {
+        Ok(result) if result.rows_affected == 1 => {}
+        Ok(_) => return HttpResponse::Unauthorized().json(ApiResponse::<()>::error("Invalid or expired MFA challenge")),
+        Err(err) => {
+            eprintln!("Database error: {:?}", err);
+            return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"));
+        }
+    }
+
+    match user::Entity::find_by_id(challenge.user_id).one(&data.db).await {
+        Ok(Some(user_model)) => {
+            tokens::issue_tokens(data, req, user_model, None, challenge.remember_me, "Login successful").await
+        }
+        Ok(None) => HttpResponse::Unauthorized().json(ApiResponse::<()>::error("Invalid or expired MFA challenge")),
+        Err(err) => {
+            eprintln!("Database error: {:?}", err);
+            HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"))
+        }
+    }
+}
+
+// Cek konfirmasi ulang faktor kedua dari user yang sudah login, supaya access token yang
+// dicuri saja tidak cukup untuk melepas MFA
+pub(crate) async fn verify_proof(
+    data: &AppState,
+    req: &HttpRequest,
+    user_id: i32,
+    proof: &SecondFactorProof,
+) -> Result<bool, HttpResponse> {
+    match (&proof.code, &proof.recovery_code, &proof.ceremony_id, &proof.credential) {
+        (Some(code), None, None, None) => totp::verify_user_code(data, user_id, code, true).await,
+        (None, Some(recovery_code), None, None) => recovery_codes::consume(data, req, user_id, recovery_code).await,
+        (None, None, Some(ceremony_id), Some(credential)) => {
+            webauthn::verify_reauthentication(data, user_id, ceremony_id, credential).await
+        }
+        _ => Err(HttpResponse::BadRequest().json(ApiResponse::<()>::error(
+            "Provide a TOTP code, a recovery code or a WebAuthn assertion",
+        ))),
+    }
+}
+
+// POST /api/auth/mfa/verify: tukar challenge token + kode TOTP (atau kode cadangan)
+// dengan access & refresh token
+pub async fn verify_mfa(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    req_body: web::Json<MfaVerifyRequest>,
+) -> impl Responder {
+    let (code, recovery_code) = match (req_body.code.as_deref(), req_body.recovery_code.as_deref()) {
+        (Some(code), None) => (Some(code), None),
+        (None, Some(recovery_code)) => (None, Some(recovery_code)),
+        _ => return HttpResponse::BadRequest().json(ApiResponse::<()>::error("Provide either code or recovery_code")),
+    };
+
+    let challenge = match start_attempt(&data.db, &req_body.challenge_token).await {
+        Ok(challenge) => challenge,
+        Err(resp) => return resp,
+    };
+
+    let verified = match (code, recovery_code) {
+        (Some(code), _) => totp::verify_user_code(&data, challenge.user_id, code, true).await,
+        (_, Some(recovery_code)) => recovery_codes::consume(&data, &req, challenge.user_id, recovery_code).await,
+        _ => Ok(false),
+    };
+
+    match verified {
+        Ok(true) => finish(&data, &req, challenge).await,
+        Ok(false) => HttpResponse::Unauthorized().json(ApiResponse::<()>::error("Invalid code")),
+        Err(resp) => resp,
+    }
+}
+
+// GET /api/auth/mfa: faktor kedua yang aktif untuk user yang sedang login
+pub async fn mfa_status(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let (_, user_id) = match extract::require_user(&req) {
+        Ok(found) => found,
+        Err(resp) => return resp,
+    };
+
+    let status = async {
+        Ok::<_, DbErr>(MfaStatusResponse {
+            totp_enabled: totp::is_enabled(&data.db, user_id).await?,
+            webauthn_enabled: webauthn::has_credentials(&data.db, user_id).await?,
+            recovery_codes_remaining: recovery_codes::remaining(&data.db, user_id).await?,
+        })
+    };
+
+    match status.await {
+        Ok(status) => HttpResponse::Ok().json(ApiResponse::success("MFA status retrieved", status)),
+        Err(err) => {
+            eprintln!("Database error: {:?}", err);
+            HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"))
+        }
+    }
+}
@@ -1,8 +1,38 @@
 pub mod register;
+pub mod api_keys;
 pub mod login;
 pub mod logout;
+pub mod refresh;
+pub mod introspect;
+pub mod sessions;
+pub mod oidc;
+pub mod magic_link;
+pub mod login_code;
+pub mod phone;
+pub mod mfa;
+pub mod totp;
+pub mod webauthn;
+pub mod recovery_codes;
+pub mod personal_tokens;
+pub mod tokens;
 
 // Re-export untuk kemudahan akses
 pub use register::register;
 pub use login::login;
-pub use logout::logout;
+pub use logout::{logout, logout_all};
+pub use refresh::refresh;
+pub use introspect::introspect;
+pub use api_keys::{list_api_keys, create_api_key, revoke_api_key};
+pub use personal_tokens::{list_personal_tokens, create_personal_token, rename_personal_token, revoke_personal_token};
+pub use oidc::{oidc_login, oidc_callback};
+pub use magic_link::{request_magic_link, verify_magic_link};
+pub use login_code::{request_login_code, verify_login_code};
+pub use phone::{start_phone_verification, confirm_phone_verification};
+pub use mfa::{verify_mfa, mfa_status};
+pub use totp::{setup_totp, confirm_totp, disable_totp};
+pub use recovery_codes::regenerate_recovery_codes;
+pub use webauthn::{
+    start_registration, finish_registration, list_credentials, delete_credential, start_reauthentication,
+    start_mfa, finish_mfa, start_passkey_login, finish_passkey_login,
+};
+pub use sessions::{list_sessions, revoke_session};
@@ -0,0 +1,162 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder, cookie::{SameSite, time::Duration as CookieDuration}};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde::{Deserialize, Serialize};
+use crate::config::cookie;
+use crate::dtos::auth_dto::{OidcCallbackQuery, OidcLoginQuery};
+use crate::dtos::common_dto::ApiResponse;
+use crate::handlers::user_handler::AppState;
+use crate::utils::identity::{self, ExternalIdentity};
+use crate::utils::oidc::OidcClient;
+use crate::utils::{hash, pkce};
+use super::mfa;
+
+// Batas waktu user menyelesaikan login di IdP
+const PENDING_LOGIN_TTL_SECS: i64 = 600;
+
+// Disimpan di cookie oidc_state selama browser berada di IdP
+#[derive(Serialize, Deserialize)]
+struct PendingLogin {
+    state: String,
+    nonce: String,
+    verifier: String,
+    remember_me: bool,
+}
+
+fn not_configured() -> HttpResponse {
+    HttpResponse::NotFound().json(ApiResponse::<()>::error("OIDC login is not configured"))
+}
+
+// GET /api/auth/oidc/login: redirect browser ke IdP
+pub async fn oidc_login(
+    data: web::Data<AppState>,
+    query: web::Query<OidcLoginQuery>,
+) -> impl Responder {
+    let Some(oidc) = &data.oidc else {
+        return not_configured();
+    };
+
+    let pending = PendingLogin {
+        state: hash::random_token(32),
+        nonce: hash::random_token(32),
+        verifier: hash::random_token(32),
+        remember_me: query.remember_me,
+    };
+
+    let url = match oidc
+        .authorization_url(&pending.state, &pending.nonce, &pkce::challenge_s256(&pending.verifier))
+        .await
+    {
+        Ok(url) => url,
+        Err(err) => {
+            eprintln!("{}", err);
+            return HttpResponse::BadGateway().json(ApiResponse::<()>::error("Identity provider is unavailable"));
+        }
+    };
+
+    let value = match serde_json::to_vec(&pending) {
+        Ok(json) => URL_SAFE_NO_PAD.encode(json),
+        Err(err) => {
+            eprintln!("Serialization error: {:?}", err);
+            return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Failed to start login"));
+        }
+    };
+    let mut state_cookie = cookie::policy().build(
+        cookie::OIDC_STATE,
+        value,
+        Some(CookieDuration::seconds(PENDING_LOGIN_TTL_SECS)),
+    );
+    // Callback datang dari redirect lintas situs, cookie Strict tidak akan terkirim
+    if state_cookie.same_site() == Some(SameSite::Strict) {
+        state_cookie.set_same_site(SameSite::Lax);
+    }
+
+    HttpResponse::Found()
+        .insert_header(("Location", url))
+        .cookie(state_cookie)
+        .finish()
+}
+
+// GET /api/auth/oidc/callback: validasi hasil login di IdP lalu login ke aplikasi
+pub async fn oidc_callback(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    query: web::Query<OidcCallbackQuery>,
+) -> impl Responder {
+    let Some(oidc) = data.oidc.clone() else {
+        return not_configured();
+    };
+
+    // Cookie state hanya berlaku untuk satu kali callback
+    let mut response = callback(&data, &req, &query, &oidc).await;
+    if let Err(err) = response.add_cookie(&cookie::policy().clear(cookie::OIDC_STATE)) {
+        eprintln!("Cookie error: {:?}", err);
+    }
+    response
+}
+
+async fn callback(
+    data: &AppState,
+    req: &HttpRequest,
+    query: &OidcCallbackQuery,
+    oidc: &OidcClient,
+) -> HttpResponse {
+    if let Some(error) = &query.error {
+        eprintln!("OIDC provider returned error: {}", error);
+        return HttpResponse::Unauthorized().json(ApiResponse::<()>::error("Login was not completed at the identity provider"));
+    }
+
+    let pending: Option<PendingLogin> = cookie::get(req, cookie::OIDC_STATE)
+        .and_then(|value| URL_SAFE_NO_PAD.decode(value).ok())
+        .and_then(|json| serde_json::from_slice(&json).ok());
+
+    // state harus sama dengan yang kita kirim dari browser yang sama (mencegah login CSRF)
+    let (Some(pending), Some(state), Some(code)) = (pending, &query.state, &query.code) else {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error("Invalid or expired login request"));
+    };
+    if !hash::constant_time_eq(&pending.state, state) {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error("Invalid or expired login request"));
+    }
+
+    let claims = match oidc.exchange_code(code, &pending.verifier).await {
+        Ok(id_token) => match oidc.validate_id_token(&id_token, &pending.nonce).await {
+            Ok(claims) => claims,
+            Err(err) => {
+                eprintln!("{}", err);
+                return HttpResponse::Unauthorized().json(ApiResponse::<()>::error("Invalid ID token"));
+            }
+        },
+        Err(err) => {
+            eprintln!("{}", err);
+            return HttpResponse::BadGateway().json(ApiResponse::<()>::error("Failed to complete login with identity provider"));
+        }
+    };
+
+    // Akun dicocokkan lewat email, jadi email wajib ada dan sudah diverifikasi IdP
+    let email = match &claims.email {
+        Some(email) if claims.email_verified => email.trim().to_lowercase(),
+        _ => {
+            return HttpResponse::Forbidden().json(ApiResponse::<()>::error("Identity provider did not return a verified email"));
+        }
+    };
+    if !oidc.is_allowed_email(&email) {
+        return HttpResponse::Forbidden().json(ApiResponse::<()>::error("Email domain is not allowed"));
+    }
+
+    let identity = ExternalIdentity {
+        issuer: claims.iss,
+        subject: claims.sub,
+        email,
+        display_name: claims.preferred_username.or(claims.name),
+    };
+
+    match identity::find_or_link_user(&data.db, &identity, true).await {
+        Ok(Some(user_model)) => {
+            mfa::complete_login(data, req, user_model, pending.remember_me).await
+        }
+        Ok(None) => HttpResponse::Forbidden().json(ApiResponse::<()>::error("An account with this email already exists and cannot be linked automatically")),
+        Err(err) => {
+            eprintln!("Database error: {:?}", err);
+            HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"))
+        }
+    }
+}
@@ -0,0 +1,182 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use chrono::{Duration, Utc};
+use sea_orm::*;
+use crate::entity::personal_access_token;
+use crate::dtos::auth_dto::{
+    CreatePersonalTokenRequest, CreatePersonalTokenResponse, PersonalTokenInfo, UpdatePersonalTokenRequest,
+};
+use crate::dtos::common_dto::ApiResponse;
+use crate::middleware::auth_middleware::extract;
+use crate::handlers::user_handler::AppState;
+use crate::utils::{hash, personal_token, scope};
+
+const DEFAULT_EXPIRY_DAYS: i64 = 30;
+const MAX_EXPIRY_DAYS: i64 = 365;
+
+fn validate_name(name: &str) -> Result<String, HttpResponse> {
+    let name = name.trim();
+    if name.is_empty() || name.len() > 100 {
+        return Err(HttpResponse::BadRequest().json(ApiResponse::<()>::error("Name must be 1-100 characters")));
+    }
+    Ok(name.to_string())
+}
+
+// List personal access token aktif milik user yang sedang login
+pub async fn list_personal_tokens(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let (_, user_id) = match extract::require_user(&req) {
+        Ok(found) => found,
+        Err(resp) => return resp,
+    };
+
+    match personal_access_token::Entity::find()
+        .filter(personal_access_token::Column::UserId.eq(user_id))
+        .filter(personal_access_token::Column::RevokedAt.is_null())
+        .filter(personal_access_token::Column::ExpiresAt.gt(Utc::now()))
+        .order_by_desc(personal_access_token::Column::CreatedAt)
+        .all(&data.db)
+        .await
+    {
+        Ok(tokens) => {
+            let tokens: Vec<PersonalTokenInfo> = tokens.into_iter().map(PersonalTokenInfo::from).collect();
+            HttpResponse::Ok().json(ApiResponse::success("Tokens retrieved", tokens))
+        }
+        Err(err) => {
+            eprintln!("Database error: {:?}", err);
+            HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"))
+        }
+    }
+}
+
+// Buat personal access token dengan scope terbatas
+pub async fn create_personal_token(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    req_body: web::Json<CreatePersonalTokenRequest>,
+) -> impl Responder {
+    let (claims, user_id) = match extract::require_user(&req) {
+        Ok(found) => found,
+        Err(resp) => return resp,
+    };
+
+    // Hanya login interaktif user sendiri yang boleh menerbitkan token baru
+    if claims.token_type != "access" || claims.scopes.is_some() || claims.is_impersonated() {
+        return HttpResponse::Forbidden().json(ApiResponse::<()>::error("Tokens can only be created from an interactive login"));
+    }
+
+    // Validasi input
+    let name = match validate_name(&req_body.name) {
+        Ok(name) => name,
+        Err(resp) => return resp,
+    };
+
+    let scopes = scope::parse(&req_body.scopes.join(" "));
+    if scopes.is_empty() {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error("At least one scope is required"));
+    }
+    if let Some(unknown) = scope::find_unknown(&scopes) {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error(&format!("Unknown scope: {}", unknown)));
+    }
+    if scopes.iter().any(|s| s == scope::ADMIN) && claims.role != "admin" {
+        return HttpResponse::Forbidden().json(ApiResponse::<()>::error("Admin scope requires an admin account"));
+    }
+
+    let days = req_body.expires_in_days.unwrap_or(DEFAULT_EXPIRY_DAYS);
+    if !(1..=MAX_EXPIRY_DAYS).contains(&days) {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error("expires_in_days must be between 1 and 365"));
+    }
+
+    let (token, prefix) = personal_token::generate();
+    let new_token = personal_access_token::ActiveModel {
+        user_id: Set(user_id),
+        name: Set(name),
+        prefix: Set(prefix),
+        token_hash: Set(hash::sha256_hex(&token)),
+        scopes: Set(scope::join(&scopes)),
+        expires_at: Set(Utc::now() + Duration::days(days)),
+        ..Default::default()
+    };
+
+    match new_token.insert(&data.db).await {
+        Ok(model) => {
+            let response = CreatePersonalTokenResponse {
+                token,
+                info: PersonalTokenInfo::from(model),
+            };
+            HttpResponse::Created().json(ApiResponse::success("Token created", response))
+        }
+        Err(err) => {
+            eprintln!("Database error: {:?}", err);
+            HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Failed to create token"))
+        }
+    }
+}
+
+// Ganti nama personal access token
+pub async fn rename_personal_token(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i32>,
+    req_body: web::Json<UpdatePersonalTokenRequest>,
+) -> impl Responder {
+    let (_, user_id) = match extract::require_user(&req) {
+        Ok(found) => found,
+        Err(resp) => return resp,
+    };
+
+    let name = match validate_name(&req_body.name) {
+        Ok(name) => name,
+        Err(resp) => return resp,
+    };
+
+    match personal_access_token::Entity::update_many()
+        .col_expr(personal_access_token::Column::Name, sea_query::Expr::value(name))
+        .filter(personal_access_token::Column::Id.eq(path.into_inner()))
+        .filter(personal_access_token::Column::UserId.eq(user_id))
+        .filter(personal_access_token::Column::RevokedAt.is_null())
+        .exec(&data.db)
+        .await
+    {
+        Ok(result) if result.rows_affected > 0 => {
+            HttpResponse::Ok().json(ApiResponse::<()>::success("Token updated", ()))
+        }
+        Ok(_) => HttpResponse::NotFound().json(ApiResponse::<()>::error("Token not found")),
+        Err(err) => {
+            eprintln!("Database error: {:?}", err);
+            HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"))
+        }
+    }
+}
+
+// Revoke personal access token milik sendiri
+pub async fn revoke_personal_token(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i32>,
+) -> impl Responder {
+    let (_, user_id) = match extract::require_user(&req) {
+        Ok(found) => found,
+        Err(resp) => return resp,
+    };
+
+    // Token milik user lain dianggap tidak ada
+    match personal_access_token::Entity::update_many()
+        .col_expr(personal_access_token::Column::RevokedAt, sea_query::Expr::value(Utc::now()))
+        .filter(personal_access_token::Column::Id.eq(path.into_inner()))
+        .filter(personal_access_token::Column::UserId.eq(user_id))
+        .filter(personal_access_token::Column::RevokedAt.is_null())
+        .exec(&data.db)
+        .await
+    {
+        Ok(result) if result.rows_affected > 0 => {
+            HttpResponse::Ok().json(ApiResponse::<()>::success("Token revoked", ()))
+        }
+        Ok(_) => HttpResponse::NotFound().json(ApiResponse::<()>::error("Token not found")),
+        Err(err) => {
+            eprintln!("Database error: {:?}", err);
+            HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"))
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use chrono::Utc;
+use sea_orm::*;
+use sea_orm::sea_query::Expr;
+use crate::entity::{login_code, user};
+use crate::dtos::auth_dto::{PhoneVerificationConfirmRequest, PhoneVerificationRequest};
+use crate::dtos::common_dto::ApiResponse;
+use crate::middleware::auth_middleware::extract;
+use crate::handlers::user_handler::AppState;
+use crate::utils::otp;
+use super::login_code::{check_code, send_code};
+
+// Disimpan di login_codes.channel, beda dari "sms" supaya kode verifikasi tidak bisa dipakai login
+const PURPOSE_PHONE_VERIFICATION: &str = "phone_verify";
+
+fn db_error(err: DbErr) -> HttpResponse {
+    eprintln!("Database error: {:?}", err);
+    HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"))
+}
+
+// true kalau nomor ini sudah diverifikasi user lain
+async fn verified_by_other(db: &impl ConnectionTrait, phone: &str, user_id: i32) -> Result<bool, DbErr> {
+    let found = user::Entity::find()
+        .filter(user::Column::Phone.eq(phone))
+        .filter(user::Column::PhoneVerifiedAt.is_not_null())
+        .filter(user::Column::Id.ne(user_id))
+        .one(db)
+        .await?;
+    Ok(found.is_some())
+}
+
+// POST /api/auth/phone/verify/start: kirim kode SMS ke nomor user (baru atau yang tersimpan)
+pub async fn start_phone_verification(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    req_body: web::Json<PhoneVerificationRequest>,
+) -> impl Responder {
+    let (_, user_id) = match extract::require_interactive_user(&req) {
+        Ok(found) => found,
+        Err(resp) => return resp,
+    };
+
+    let user_model = match user::Entity::find_by_id(user_id).one(&data.db).await {
+        Ok(Some(user_model)) => user_model,
+        Ok(None) => return HttpResponse::NotFound().json(ApiResponse::<()>::error("User not found")),
+        Err(err) => return db_error(err),
+    };
+
+    let requested = req_body.phone.as_deref().map(str::trim).filter(|p| !p.is_empty());
+    let phone = match requested {
+        Some(phone) => match otp::normalize_phone(phone) {
+            Some(phone) => phone,
+            None => {
+                return HttpResponse::BadRequest().json(ApiResponse::<()>::error("Phone number must be in international format, e.g. +628123456789"));
+            }
+        },
+        None => match &user_model.phone {
+            Some(phone) => phone.clone(),
+            None => return HttpResponse::BadRequest().json(ApiResponse::<()>::error("Add a phone number first")),
+        },
+    };
+
+    if user_model.phone.as_deref() == Some(phone.as_str()) && user_model.phone_verified_at.is_some() {
+        return HttpResponse::Conflict().json(ApiResponse::<()>::error("Phone number is already verified"));
+    }
+
+    match verified_by_other(&data.db, &phone, user_id).await {
+        Ok(false) => {}
+        Ok(true) => return HttpResponse::Conflict().json(ApiResponse::<()>::error("Phone number already registered")),
+        Err(err) => return db_error(err),
+    }
+
+    // Nomor baru menggantikan nomor lama dan belum bisa dipakai login sampai dikonfirmasi.
+    // Kode yang sudah dikirim ke nomor lama ikut hangus, walaupun kode baru masih tertahan cooldown.
+    if user_model.phone.as_deref() != Some(phone.as_str()) {
+        let changed = async {
+            let txn = data.db.begin().await?;
+            login_code::Entity::update_many()
+                .col_expr(login_code::Column::ConsumedAt, Expr::value(Utc::now()))
+                .filter(login_code::Column::UserId.eq(user_id))
+                .filter(login_code::Column::Channel.eq(PURPOSE_PHONE_VERIFICATION))
+                .filter(login_code::Column::ConsumedAt.is_null())
+                .exec(&txn)
+                .await?;
+
+            let mut active: user::ActiveModel = user_model.into();
+            active.phone = Set(Some(phone.clone()));
+            active.phone_verified_at = Set(None);
+            active.update(&txn).await?;
+            txn.commit().await
+        };
+        if let Err(err) = changed.await {
+            return db_error(err);
+        }
+    }
+
+    match send_code(&data, user_id, PURPOSE_PHONE_VERIFICATION, otp::CHANNEL_SMS, &phone).await {
+        Ok(true) => HttpResponse::Ok().json(ApiResponse::<()>::success("Verification code sent", ())),
+        Ok(false) => HttpResponse::TooManyRequests().json(ApiResponse::<()>::error("Please wait before requesting another code")),
+        Err(resp) => resp,
+    }
+}
+
+// POST /api/auth/phone/verify: konfirmasi nomor dengan kode dari SMS
+pub async fn confirm_phone_verification(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    req_body: web::Json<PhoneVerificationConfirmRequest>,
+) -> impl Responder {
+    let (_, user_id) = match extract::require_interactive_user(&req) {
+        Ok(found) => found,
+        Err(resp) => return resp,
+    };
+
+    let phone = match user::Entity::find_by_id(user_id).one(&data.db).await {
+        Ok(Some(user::Model { phone: Some(phone), phone_verified_at: None, .. })) => phone,
+        Ok(_) => return HttpResponse::BadRequest().json(ApiResponse::<()>::error("No phone number awaiting verification")),
+        Err(err) => return db_error(err),
+    };
+
+    // Kode lama hangus setiap kali nomor diganti, jadi kode yang valid selalu
+    // dikirim ke nomor yang sedang tersimpan
+    match check_code(&data, user_id, PURPOSE_PHONE_VERIFICATION, &req_body.code).await {
+        Ok(true) => {}
+        Ok(false) => return HttpResponse::BadRequest().json(ApiResponse::<()>::error("Invalid or expired code")),
+        Err(resp) => return resp,
+    }
+
+    let verified = async {
+        let txn = data.db.begin().await?;
+
+        if verified_by_other(&txn, &phone, user_id).await? {
+            return Ok(false);
+        }
+
+        // Pemilik asli nomor menang: lepaskan nomor ini dari akun lain yang belum memverifikasinya
+        user::Entity::update_many()
+            .col_expr(user::Column::Phone, Expr::value(Option::<String>::None))
+            .filter(user::Column::Phone.eq(&phone))
+            .filter(user::Column::PhoneVerifiedAt.is_null())
+            .filter(user::Column::Id.ne(user_id))
+            .exec(&txn)
+            .await?;
+
+        let result = user::Entity::update_many()
+            .col_expr(user::Column::PhoneVerifiedAt, Expr::value(Utc::now()))
+            .filter(user::Column::Id.eq(user_id))
+            .filter(user::Column::Phone.eq(&phone))
+            .exec(&txn)
+            .await?;
+
+        txn.commit().await?;
+        Ok::<_, DbErr>(result.rows_affected == 1)
+    };
+
+    // Cek verified_by_other tidak mengunci baris; konfirmasi paralel untuk nomor yang sama
+    // ditolak oleh unique index users.verified_phone
+    match verified.await {
+        Ok(true) => HttpResponse::Ok().json(ApiResponse::<()>::success("Phone number verified", ())),
+        Ok(false) => HttpResponse::Conflict().json(ApiResponse::<()>::error("Phone number already registered")),
+        Err(err) if matches!(err.sql_err(), Some(SqlErr::UniqueConstraintViolation(_))) => {
+            HttpResponse::Conflict().json(ApiResponse::<()>::error("Phone number already registered"))
+        }
+        Err(err) => db_error(err),
+    }
+}
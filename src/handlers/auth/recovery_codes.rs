@@ -0,0 +1,204 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use chrono::Utc;
+use rand::{rngs::OsRng, Rng};
+use sea_orm::*;
+use sea_orm::sea_query::Expr;
+use crate::entity::{mfa_recovery_code, user};
+use crate::dtos::common_dto::ApiResponse;
+use crate::dtos::mfa_dto::{RecoveryCodesResponse, SecondFactorProof};
+use crate::middleware::auth_middleware::extract;
+use crate::handlers::user_handler::AppState;
+use crate::utils::hash;
+use crate::utils::mailer::Email;
+use super::{mfa, totp, webauthn};
+
+// Jumlah kode per batch
+const BATCH_SIZE: usize = 10;
+
+// 16 karakter dari 29 simbol (~78 bit), tanpa huruf/angka yang mirip (0/O, 1/I/L, U/V)
+const CODE_LEN: usize = 16;
+const ALPHABET: &[u8] = b"ABCDEFGHJKMNPQRSTWXYZ23456789";
+
+// Format tampilan: XXXX-XXXX-XXXX-XXXX
+fn generate_code() -> String {
+    let mut code = String::with_capacity(CODE_LEN + 3);
+    for i in 0..CODE_LEN {
+        if i > 0 && i % 4 == 0 {
+            code.push('-');
+        }
+        code.push(ALPHABET[OsRng.gen_range(0..ALPHABET.len())] as char);
+    }
+    code
+}
+
+// Terima kode dengan huruf kecil, spasi atau tanpa tanda hubung
+fn normalize(code: &str) -> String {
+    code.chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+// Ganti seluruh batch milik user dengan kode baru; kode lama langsung tidak berlaku
+pub(crate) async fn replace_batch(db: &DatabaseConnection, user_id: i32) -> Result<Vec<String>, DbErr> {
+    let codes: Vec<String> = (0..BATCH_SIZE).map(|_| generate_code()).collect();
+    let models: Vec<mfa_recovery_code::ActiveModel> = codes
+        .iter()
+        .map(|code| mfa_recovery_code::ActiveModel {
+            user_id: Set(user_id),
+            code_hash: Set(hash::sha256_hex(&normalize(code))),
+            ..Default::default()
+        })
+        .collect();
+
+    let txn = db.begin().await?;
+    mfa_recovery_code::Entity::delete_many()
+        .filter(mfa_recovery_code::Column::UserId.eq(user_id))
+        .exec(&txn)
+        .await?;
+    mfa_recovery_code::Entity::insert_many(models).exec(&txn).await?;
+    txn.commit().await?;
+
+    Ok(codes)
+}
+
+// Dipanggil saat enrollment faktor kedua: buat batch hanya kalau user belum punya kode yang tersisa
+pub(crate) async fn issue_if_missing(db: &DatabaseConnection, user_id: i32) -> Result<Option<Vec<String>>, DbErr> {
+    if remaining(db, user_id).await? > 0 {
+        return Ok(None);
+    }
+    replace_batch(db, user_id).await.map(Some)
+}
+
+// Jumlah kode yang belum dipakai
+pub(crate) async fn remaining(db: &DatabaseConnection, user_id: i32) -> Result<u64, DbErr> {
+    mfa_recovery_code::Entity::find()
+        .filter(mfa_recovery_code::Column::UserId.eq(user_id))
+        .filter(mfa_recovery_code::Column::UsedAt.is_null())
+        .count(db)
+        .await
+}
+
+// Hapus kode cadangan kalau faktor kedua terakhir sudah dilepas
+pub(crate) async fn clear_if_mfa_disabled(db: &DatabaseConnection, user_id: i32) -> Result<(), DbErr> {
+    if totp::is_enabled(db, user_id).await? || webauthn::has_credentials(db, user_id).await? {
+        return Ok(());
+    }
+    mfa_recovery_code::Entity::delete_many()
+        .filter(mfa_recovery_code::Column::UserId.eq(user_id))
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+// Pakai satu kode cadangan. Ditandai terpakai secara atomik, jadi request paralel
+// dengan kode yang sama hanya satu yang lolos. User selalu diberi tahu lewat email.
+pub(crate) async fn consume(
+    data: &AppState,
+    req: &HttpRequest,
+    user_id: i32,
+    code: &str,
+) -> Result<bool, HttpResponse> {
+    let db_error = |err: DbErr| {
+        eprintln!("Database error: {:?}", err);
+        HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"))
+    };
+
+    let code = normalize(code);
+    if code.len() != CODE_LEN {
+        return Ok(false);
+    }
+
+    let result = mfa_recovery_code::Entity::update_many()
+        .col_expr(mfa_recovery_code::Column::UsedAt, Expr::value(Utc::now()))
+        .filter(mfa_recovery_code::Column::UserId.eq(user_id))
+        .filter(mfa_recovery_code::Column::CodeHash.eq(hash::sha256_hex(&code)))
+        .filter(mfa_recovery_code::Column::UsedAt.is_null())
+        .exec(&data.db)
+        .await
+        .map_err(db_error)?;
+
+    if result.rows_affected != 1 {
+        return Ok(false);
+    }
+
+    let left = remaining(&data.db, user_id).await.map_err(db_error)?;
+    notify_used(data, req, user_id, left).await;
+    Ok(true)
+}
+
+// Email keamanan setiap kali kode cadangan dipakai. Gagal kirim hanya dicatat.
+async fn notify_used(data: &AppState, req: &HttpRequest, user_id: i32, remaining: u64) {
+    let user_model = match user::Entity::find_by_id(user_id).one(&data.db).await {
+        Ok(Some(user_model)) => user_model,
+        Ok(None) => return,
+        Err(err) => {
+            eprintln!("Database error: {:?}", err);
+            return;
+        }
+    };
+
+    let ip_address = req.connection_info().realip_remote_addr().unwrap_or("unknown").to_string();
+    let email = Email {
+        to: user_model.email.clone(),
+        subject: "A recovery code was used on your account".to_string(),
+        body: format!(
+            "Hi {},\n\nA recovery code was just used on your account from {} at {}.\n\
+             You have {} recovery code(s) left.\n\n\
+             If this was not you, change your password and regenerate your recovery codes immediately.",
+            user_model.username,
+            ip_address,
+            Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
+            remaining
+        ),
+    };
+
+    if let Err(err) = data.mailer.send(&email).await {
+        eprintln!("Mail error: {}", err);
+    }
+}
+
+// POST /api/auth/mfa/recovery-codes: buat batch baru, batch lama tidak berlaku lagi.
+// Wajib konfirmasi ulang faktor kedua, supaya access token saja tidak bisa membuat jalan pintas MFA
+pub async fn regenerate_recovery_codes(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    req_body: web::Json<SecondFactorProof>,
+) -> impl Responder {
+    let (_, user_id) = match extract::require_interactive_user(&req) {
+        Ok(found) => found,
+        Err(resp) => return resp,
+    };
+
+    let enabled = async {
+        Ok::<_, DbErr>(totp::is_enabled(&data.db, user_id).await? || webauthn::has_credentials(&data.db, user_id).await?)
+    };
+
+    match enabled.await {
+        Ok(true) => {}
+        Ok(false) => {
+            return HttpResponse::BadRequest().json(ApiResponse::<()>::error("Enable a second factor before generating recovery codes"));
+        }
+        Err(err) => {
+            eprintln!("Database error: {:?}", err);
+            return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"));
+        }
+    }
+
+    match mfa::verify_proof(&data, &req, user_id, &req_body).await {
+        Ok(true) => {}
+        Ok(false) => return HttpResponse::Unauthorized().json(ApiResponse::<()>::error("Second factor verification failed")),
+        Err(resp) => return resp,
+    }
+
+    match replace_batch(&data.db, user_id).await {
+        Ok(recovery_codes) => HttpResponse::Ok().json(ApiResponse::success(
+            "Recovery codes generated, store them somewhere safe",
+            RecoveryCodesResponse { recovery_codes },
+        )),
+        Err(err) => {
+            eprintln!("Database error: {:?}", err);
+            HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"))
+        }
+    }
+}
@@ -0,0 +1,130 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use sea_orm::*;
+use sea_orm::sea_query::Expr;
+use chrono::Utc;
+use crate::entity::{refresh_token, user};
+use crate::dtos::auth_dto::RefreshRequest;
+use crate::dtos::common_dto::ApiResponse;
+use crate::config::cookie;
+use crate::middleware::csrf;
+use crate::utils::{hash, jwt};
+use crate::handlers::user_handler::AppState;
+use super::tokens;
+
+pub async fn refresh(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    req_body: Option<web::Json<RefreshRequest>>,
+) -> impl Responder {
+    // Ambil refresh token dari body, fallback ke cookie
+    let from_body = req_body
+        .and_then(|body| body.into_inner().refresh_token)
+        .filter(|t| !t.trim().is_empty());
+
+    // Refresh lewat cookie dikirim otomatis oleh browser, wajib CSRF token
+    let from_cookie = cookie::get(&req, cookie::REFRESH_TOKEN);
+    if from_body.is_none() && from_cookie.is_some() && !csrf::verify(&req) {
+        return HttpResponse::Forbidden().json(ApiResponse::<()>::error("Invalid or missing CSRF token"));
+    }
+
+    let token = from_body.or(from_cookie);
+
+    let token = match token {
+        Some(token) => token,
+        None => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()>::error("Missing refresh token"));
+        }
+    };
+
+    // Validate token
+    let claims = match jwt::validate_token(&token) {
+        Ok(claims) => claims,
+        Err(err) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()>::error(jwt::rejection_message(&err)));
+        }
+    };
+
+    // Access token tidak boleh dipakai untuk refresh
+    if claims.token_type != "refresh" {
+        return HttpResponse::Unauthorized().json(ApiResponse::<()>::error("Invalid token type"));
+    }
+
+    match data.denylist.is_revoked(&claims.jti).await {
+        Ok(false) => {}
+        Ok(true) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()>::error("Refresh token has been revoked"));
+        }
+        Err(err) => {
+            eprintln!("Database error: {:?}", err);
+            return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"));
+        }
+    }
+
+    // Cari token yang tersimpan berdasarkan hash-nya
+    let stored = match refresh_token::Entity::find()
+        .filter(refresh_token::Column::TokenHash.eq(hash::sha256_hex(&token)))
+        .one(&data.db)
+        .await
+    {
+        Ok(Some(stored)) => stored,
+        Ok(None) => {
+            return HttpResponse::Unauthorized().json(ApiResponse::<()>::error("Invalid or expired refresh token"));
+        }
+        Err(err) => {
+            eprintln!("Database error: {:?}", err);
+            return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"));
+        }
+    };
+
+    // Refresh token milik client OAuth hanya bisa dipakai di /oauth/token
+    if claims.user_id() != Some(stored.user_id) || claims.jti != stored.jti || stored.client_id.is_some() {
+        return HttpResponse::Unauthorized().json(ApiResponse::<()>::error("Invalid or expired refresh token"));
+    }
+
+    if stored.revoked {
+        return HttpResponse::Unauthorized().json(ApiResponse::<()>::error("Refresh token has been revoked"));
+    }
+
+    // Tandai token sebagai sudah dipakai. Update bersyarat `used_at IS NULL` supaya
+    // dua request paralel dengan token yang sama tidak bisa sama-sama lolos.
+    let marked = refresh_token::Entity::update_many()
+        .col_expr(refresh_token::Column::UsedAt, Expr::value(Utc::now()))
+        .filter(refresh_token::Column::Id.eq(stored.id))
+        .filter(refresh_token::Column::UsedAt.is_null())
+        .exec(&data.db)
+        .await;
+
+    match marked {
+        Ok(result) if result.rows_affected == 1 => {}
+        Ok(_) => {
+            // Token yang sudah pernah dirotasi dipakai lagi -> kemungkinan dicuri.
+            // Matikan seluruh family supaya pemegang token curian & asli harus login ulang.
+            eprintln!("Refresh token reuse detected for family {}", stored.family_id);
+            if let Err(err) = tokens::revoke_family(&data, &stored.family_id).await {
+                eprintln!("Database error: {:?}", err);
+            }
+            return HttpResponse::Unauthorized().json(ApiResponse::<()>::error("Refresh token reuse detected"));
+        }
+        Err(err) => {
+            eprintln!("Database error: {:?}", err);
+            return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"));
+        }
+    }
+
+    // Pastikan user masih ada, data terbaru (role/email) dipakai untuk token baru
+    match user::Entity::find_by_id(stored.user_id).one(&data.db).await {
+        Ok(Some(user_model)) if user_model.token_version != claims.ver => {
+            HttpResponse::Unauthorized().json(ApiResponse::<()>::error("Refresh token has been revoked"))
+        }
+        Ok(Some(user_model)) => {
+            tokens::issue_tokens(&data, &req, user_model, Some(&stored), stored.remember_me, "Token refreshed successfully").await
+        }
+        Ok(None) => {
+            HttpResponse::Unauthorized().json(ApiResponse::<()>::error("User no longer exists"))
+        }
+        Err(err) => {
+            eprintln!("Database error: {:?}", err);
+            HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"))
+        }
+    }
+}
@@ -3,7 +3,7 @@ use sea_orm::*;
 use crate::entity::user;
 use crate::dtos::auth_dto::{RegisterRequest, RegisterResponse, UserInfo};
 use crate::dtos::common_dto::ApiResponse;
-use crate::utils::hash;
+use crate::utils::{hash, otp};
 use crate::handlers::user_handler::AppState;
 
 pub async fn register(
@@ -23,6 +23,16 @@ pub async fn register(
         return HttpResponse::BadRequest().json(ApiResponse::<()>::error("Password must be at least 6 characters"));
     }
 
+    let phone = match req_body.phone.as_deref().map(str::trim).filter(|p| !p.is_empty()) {
+        Some(phone) => match otp::normalize_phone(phone) {
+            Some(phone) => Some(phone),
+            None => {
+                return HttpResponse::BadRequest().json(ApiResponse::<()>::error("Phone number must be in international format, e.g. +628123456789"));
+            }
+        },
+        None => None,
+    };
+
     // Cek apakah email sudah terdaftar
     match user::Entity::find()
         .filter(user::Column::Email.eq(&req_body.email))
@@ -39,6 +49,26 @@ pub async fn register(
         _ => {}
     }
 
+    // Nomor baru tersimpan tanpa verifikasi dan belum bisa dipakai login. Yang ditolak hanya
+    // nomor yang sudah diverifikasi user lain, supaya nomor tidak bisa "dikunci" orang lain.
+    if let Some(phone) = &phone {
+        match user::Entity::find()
+            .filter(user::Column::Phone.eq(phone))
+            .filter(user::Column::PhoneVerifiedAt.is_not_null())
+            .one(&data.db)
+            .await
+        {
+            Ok(Some(_)) => {
+                return HttpResponse::Conflict().json(ApiResponse::<()>::error("Phone number already registered"));
+            }
+            Err(err) => {
+                eprintln!("Database error: {:?}", err);
+                return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"));
+            }
+            _ => {}
+        }
+    }
+
     // Hash password
     let password_hash = match hash::hash_password(&req_body.password) {
         Ok(hash) => hash,
@@ -53,8 +83,11 @@ pub async fn register(
         id: NotSet,
         username: Set(req_body.username.trim().to_string()),
         email: Set(req_body.email.trim().to_lowercase()),
+        phone: Set(phone),
+        phone_verified_at: NotSet,
         password_hash: Set(password_hash),
         role: Set("user".to_string()), // Default role
+        token_version: NotSet,
         created_at: NotSet,
         updated_at: NotSet,
     };
@@ -0,0 +1,82 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use chrono::Utc;
+use sea_orm::*;
+use crate::entity::user_session;
+use crate::dtos::auth_dto::SessionInfo;
+use crate::dtos::common_dto::ApiResponse;
+use crate::middleware::auth_middleware::extract;
+use crate::handlers::user_handler::AppState;
+use super::tokens;
+
+// List session aktif milik user yang sedang login
+pub async fn list_sessions(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let (claims, user_id) = match extract::require_user(&req) {
+        Ok(found) => found,
+        Err(resp) => return resp,
+    };
+
+    match user_session::Entity::find()
+        .filter(user_session::Column::UserId.eq(user_id))
+        .filter(user_session::Column::RevokedAt.is_null())
+        .filter(user_session::Column::ExpiresAt.gt(Utc::now()))
+        .order_by_desc(user_session::Column::LastUsedAt)
+        .all(&data.db)
+        .await
+    {
+        Ok(sessions) => {
+            let sessions: Vec<SessionInfo> = sessions
+                .into_iter()
+                .map(|session| SessionInfo {
+                    current: claims.sid.as_deref() == Some(session.id.as_str()),
+                    id: session.id,
+                    user_agent: session.user_agent,
+                    ip_address: session.ip_address,
+                    created_at: session.created_at,
+                    last_used_at: session.last_used_at,
+                    expires_at: session.expires_at,
+                })
+                .collect();
+            HttpResponse::Ok().json(ApiResponse::success("Sessions retrieved", sessions))
+        }
+        Err(err) => {
+            eprintln!("Database error: {:?}", err);
+            HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"))
+        }
+    }
+}
+
+// Revoke satu session milik user (logout dari device tersebut)
+pub async fn revoke_session(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+) -> impl Responder {
+    let (_, user_id) = match extract::require_user(&req) {
+        Ok(found) => found,
+        Err(resp) => return resp,
+    };
+
+    // Session milik user lain dianggap tidak ada
+    match user_session::Entity::find_by_id(path.into_inner())
+        .filter(user_session::Column::UserId.eq(user_id))
+        .filter(user_session::Column::RevokedAt.is_null())
+        .one(&data.db)
+        .await
+    {
+        Ok(Some(session)) => match tokens::revoke_family(&data, &session.id).await {
+            Ok(()) => HttpResponse::Ok().json(ApiResponse::<()>::success("Session revoked", ())),
+            Err(err) => {
+                eprintln!("Database error: {:?}", err);
+                HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"))
+            }
+        },
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()>::error("Session not found")),
+        Err(err) => {
+            eprintln!("Database error: {:?}", err);
+            HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"))
+        }
+    }
+}
@@ -0,0 +1,279 @@
+use actix_web::{HttpRequest, HttpResponse, cookie::time::Duration as CookieDuration};
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::*;
+use sea_orm::sea_query::Expr;
+use uuid::Uuid;
+use crate::entity::{refresh_token, user, user_session};
+use crate::config::auth::{self, AuthMode};
+use crate::config::cookie;
+use crate::dtos::auth_dto::{LoginResponse, SessionLoginResponse, UserInfo};
+use crate::dtos::common_dto::ApiResponse;
+use crate::utils::{hash, jwt};
+use crate::middleware::csrf;
+use crate::utils::session_store::ServerSession;
+use crate::handlers::user_handler::AppState;
+
+// Generate access & refresh token untuk user, lalu kirim sebagai cookie + body LoginResponse.
+// Dipakai bersama oleh login dan refresh supaya bentuk response-nya selalu sama.
+// `parent` diisi saat rotasi: token baru masuk ke family (session) yang sama dengan token lama,
+// tanpa parent berarti login baru dan session baru dibuat.
+// `remember_me` menentukan umur refresh token & apakah cookie-nya persistent;
+// saat rotasi nilainya diwarisi dari parent.
+pub async fn issue_tokens(
+    data: &AppState,
+    req: &HttpRequest,
+    user_model: user::Model,
+    parent: Option<&refresh_token::Model>,
+    remember_me: bool,
+    message: &str,
+) -> HttpResponse {
+    let remember_me = parent.map(|p| p.remember_me).unwrap_or(remember_me);
+
+    // Mode session: tidak ada JWT, browser cukup membawa session id
+    if data.auth_mode == AuthMode::Session {
+        return issue_session(data, req, user_model, remember_me, message).await;
+    }
+
+    let db = &data.db;
+    let family_id = parent
+        .map(|p| p.family_id.clone())
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+    let mut access_claims = jwt::Claims::new_access_token(
+        user_model.id,
+        user_model.email.clone(),
+        user_model.role.clone(),
+        user_model.token_version,
+        Duration::seconds(auth::access_token_ttl_secs()),
+    );
+    let mut refresh_claims = jwt::Claims::new_refresh_token(
+        user_model.id,
+        user_model.email.clone(),
+        user_model.role.clone(),
+        user_model.token_version,
+        Duration::seconds(auth::refresh_token_ttl_secs(remember_me)),
+    );
+    access_claims.sid = Some(family_id.clone());
+    refresh_claims.sid = Some(family_id.clone());
+
+    let (access, refresh) = match (jwt::encode_claims(&access_claims), jwt::encode_claims(&refresh_claims)) {
+        (Ok(access), Ok(refresh)) => (access, refresh),
+        _ => {
+            eprintln!("JWT generation error");
+            return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Failed to generate tokens"));
+        }
+    };
+
+    // Simpan refresh token (hash-nya saja) supaya bisa dirotasi & dilacak
+    let new_token = refresh_token::ActiveModel {
+        id: NotSet,
+        user_id: Set(user_model.id),
+        jti: Set(refresh_claims.jti.clone()),
+        token_hash: Set(hash::sha256_hex(&refresh)),
+        family_id: Set(family_id.clone()),
+        parent_id: Set(parent.map(|p| p.id)),
+        expires_at: Set(DateTime::from_timestamp(refresh_claims.exp, 0).unwrap_or_default()),
+        revoked: Set(false),
+        used_at: Set(None),
+        remember_me: Set(remember_me),
+        client_id: Set(None),
+        scopes: Set(None),
+        created_at: NotSet,
+    };
+
+    // Catat session (device) untuk login baru, atau perbarui saat rotasi
+    let now = Utc::now();
+    let session_result = if parent.is_none() {
+        create_device_session(
+            db,
+            req,
+            user_model.id,
+            &family_id,
+            Some(&access_claims),
+            DateTime::from_timestamp(refresh_claims.exp, 0).unwrap_or_default(),
+        )
+        .await
+    } else {
+        user_session::Entity::update_many()
+            .col_expr(user_session::Column::AccessJti, Expr::value(access_claims.jti.clone()))
+            .col_expr(user_session::Column::AccessExpiresAt, Expr::value(DateTime::from_timestamp(access_claims.exp, 0)))
+            .col_expr(user_session::Column::ExpiresAt, Expr::value(DateTime::from_timestamp(refresh_claims.exp, 0).unwrap_or_default()))
+            .col_expr(user_session::Column::LastUsedAt, Expr::value(now))
+            .filter(user_session::Column::Id.eq(&family_id))
+            .exec(db)
+            .await
+            .map(|_| ())
+    };
+
+    if let Err(err) = session_result {
+        eprintln!("Error storing session: {:?}", err);
+        return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Failed to generate tokens"));
+    }
+
+    if let Err(err) = refresh_token::Entity::insert(new_token).exec(db).await {
+        eprintln!("Error storing refresh token: {:?}", err);
+        return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Failed to generate tokens"));
+    }
+
+    // Set cookies untuk access & refresh token, atributnya mengikuti CookiePolicy.
+    // Tanpa remember me semua cookie jadi session cookie (hilang saat browser ditutup).
+    let policy = cookie::policy();
+    let max_age = |claims: &jwt::Claims| remember_me.then(|| CookieDuration::seconds(claims.lifetime_secs()));
+    let access_cookie = policy.build(cookie::ACCESS_TOKEN, access.clone(), max_age(&access_claims));
+    let refresh_cookie = policy.build(cookie::REFRESH_TOKEN, refresh.clone(), max_age(&refresh_claims));
+
+    let response = LoginResponse {
+        access_token: access,
+        refresh_token: refresh,
+        token_type: "Bearer".to_string(),
+        expires_in: access_claims.lifetime_secs(),
+        user: UserInfo {
+            id: user_model.id,
+            username: user_model.username,
+            email: user_model.email,
+            role: user_model.role,
+        },
+    };
+
+    HttpResponse::Ok()
+        .cookie(access_cookie)
+        .cookie(refresh_cookie)
+        .cookie(csrf::new_cookie(max_age(&refresh_claims)))
+        .json(ApiResponse::success(message, response))
+}
+
+// Login untuk AUTH_MODE=session: session id acak di cookie, datanya di SessionStore.
+// Tetap dicatat di user_sessions supaya muncul di daftar device dan bisa di-revoke.
+async fn issue_session(
+    data: &AppState,
+    req: &HttpRequest,
+    user_model: user::Model,
+    remember_me: bool,
+    message: &str,
+) -> HttpResponse {
+    let ttl = auth::refresh_token_ttl_secs(remember_me);
+    let now = Utc::now();
+    let expires_at = now + Duration::seconds(ttl);
+    let session_id = Uuid::new_v4().to_string();
+    let raw_id = hash::random_token(32);
+
+    if let Err(err) = create_device_session(&data.db, req, user_model.id, &session_id, None, expires_at).await {
+        eprintln!("Error storing session: {:?}", err);
+        return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Failed to create session"));
+    }
+
+    let session = ServerSession {
+        id_hash: hash::sha256_hex(&raw_id),
+        session_id,
+        user_id: user_model.id,
+        email: user_model.email.clone(),
+        role: user_model.role.clone(),
+        token_version: user_model.token_version,
+        created_at: now,
+        expires_at,
+    };
+
+    if let Err(err) = data.sessions.create(session).await {
+        eprintln!("Error storing session: {:?}", err);
+        return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Failed to create session"));
+    }
+
+    let max_age = remember_me.then(|| CookieDuration::seconds(ttl));
+    let session_cookie = cookie::policy().build(cookie::SESSION_ID, raw_id, max_age);
+
+    let response = SessionLoginResponse {
+        token_type: "Session".to_string(),
+        expires_in: ttl,
+        user: UserInfo {
+            id: user_model.id,
+            username: user_model.username,
+            email: user_model.email,
+            role: user_model.role,
+        },
+    };
+
+    HttpResponse::Ok()
+        .cookie(session_cookie)
+        .cookie(csrf::new_cookie(max_age))
+        .json(ApiResponse::success(message, response))
+}
+
+// Catat login baru di user_sessions (daftar device)
+async fn create_device_session(
+    db: &DatabaseConnection,
+    req: &HttpRequest,
+    user_id: i32,
+    session_id: &str,
+    access_claims: Option<&jwt::Claims>,
+    expires_at: DateTime<Utc>,
+) -> Result<(), DbErr> {
+    let now = Utc::now();
+    let session = user_session::ActiveModel {
+        id: Set(session_id.to_string()),
+        user_id: Set(user_id),
+        user_agent: Set(req
+            .headers()
+            .get("User-Agent")
+            .and_then(|h| h.to_str().ok())
+            .map(|ua| ua.chars().take(512).collect())),
+        ip_address: Set(req.connection_info().realip_remote_addr().map(|ip| ip.to_string())),
+        access_jti: Set(access_claims.map(|c| c.jti.clone())),
+        access_expires_at: Set(access_claims.and_then(|c| DateTime::from_timestamp(c.exp, 0))),
+        expires_at: Set(expires_at),
+        created_at: Set(Some(now)),
+        last_used_at: Set(Some(now)),
+        revoked_at: Set(None),
+    };
+    user_session::Entity::insert(session).exec(db).await?;
+    Ok(())
+}
+
+// Revoke satu session: semua refresh token dalam family-nya dimatikan, access token
+// terakhirnya masuk denylist, dan access token lain dengan sid yang sama ditolak JwtMiddleware
+// lewat revoked_sessions. Dipakai saat logout, hapus session dan saat reuse terdeteksi.
+pub async fn revoke_family(data: &AppState, family_id: &str) -> Result<(), DbErr> {
+    refresh_token::Entity::update_many()
+        .col_expr(refresh_token::Column::Revoked, Expr::value(true))
+        .filter(refresh_token::Column::FamilyId.eq(family_id))
+        .exec(&data.db)
+        .await?;
+
+    data.sessions.delete_by_session_id(family_id).await?;
+
+    let session = user_session::Entity::find_by_id(family_id.to_string())
+        .one(&data.db)
+        .await?;
+
+    if let Some(session) = session {
+        if let (Some(jti), Some(exp)) = (&session.access_jti, session.access_expires_at) {
+            data.denylist.revoke(jti, exp.timestamp()).await?;
+        }
+
+        if session.revoked_at.is_none() {
+            let mut active: user_session::ActiveModel = session.into();
+            active.revoked_at = Set(Some(Utc::now()));
+            active.update(&data.db).await?;
+        }
+        data.revoked_sessions.mark_revoked(family_id);
+    }
+
+    Ok(())
+}
+
+// "Logout everywhere": naikkan token_version (semua access token langsung stale)
+// lalu revoke semua session aktif milik user.
+pub async fn revoke_all_for_user(data: &AppState, user_id: i32) -> Result<(), DbErr> {
+    data.token_versions.bump(&data.db, user_id).await?;
+
+    let sessions = user_session::Entity::find()
+        .filter(user_session::Column::UserId.eq(user_id))
+        .filter(user_session::Column::RevokedAt.is_null())
+        .all(&data.db)
+        .await?;
+
+    for session in sessions {
+        revoke_family(data, &session.id).await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,235 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use chrono::Utc;
+use sea_orm::*;
+use sea_orm::sea_query::{Condition, Expr};
+use crate::entity::user_totp;
+use crate::dtos::common_dto::ApiResponse;
+use crate::dtos::mfa_dto::{RecoveryCodesResponse, SecondFactorProof, TotpCodeRequest, TotpSetupResponse};
+use crate::middleware::auth_middleware::extract;
+use crate::handlers::user_handler::AppState;
+use crate::utils::secret_box::SecretBox;
+use crate::utils::totp;
+use super::{mfa, recovery_codes};
+
+// Associated data enkripsi seed, mengikat ciphertext ke user pemiliknya
+fn secret_context(user_id: i32) -> String {
+    format!("user_totp:{}", user_id)
+}
+
+fn secret_box(data: &AppState) -> Result<&SecretBox, HttpResponse> {
+    data.secret_box.as_ref().ok_or_else(|| {
+        eprintln!("MFA_ENCRYPTION_KEY is not set");
+        HttpResponse::ServiceUnavailable().json(ApiResponse::<()>::error("Multi-factor authentication is not configured"))
+    })
+}
+
+// Cek kode TOTP milik user. Step yang dipakai langsung dicatat supaya kode yang sama
+// tidak bisa dipakai ulang, termasuk oleh request paralel.
+// `confirmed` memilih authenticator yang sudah aktif atau yang masih dalam enrollment.
+pub(crate) async fn verify_user_code(
+    data: &AppState,
+    user_id: i32,
+    code: &str,
+    confirmed: bool,
+) -> Result<bool, HttpResponse> {
+    let db_error = |err: DbErr| {
+        eprintln!("Database error: {:?}", err);
+        HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"))
+    };
+
+    let found = user_totp::Entity::find()
+        .filter(user_totp::Column::UserId.eq(user_id))
+        .one(&data.db)
+        .await
+        .map_err(db_error)?;
+    let Some(stored) = found.filter(|t| t.confirmed_at.is_some() == confirmed) else {
+        return Ok(false);
+    };
+
+    let secret = secret_box(data)?
+        .decrypt(&stored.secret_encrypted, &secret_context(user_id))
+        .ok_or_else(|| {
+            eprintln!("Failed to decrypt TOTP secret for user {}", user_id);
+            HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Authentication error"))
+        })?;
+
+    let Some(step) = totp::verify(&secret, code, stored.last_used_step) else {
+        return Ok(false);
+    };
+
+    let result = user_totp::Entity::update_many()
+        .col_expr(user_totp::Column::LastUsedStep, Expr::value(step))
+        .filter(user_totp::Column::Id.eq(stored.id))
+        .filter(
+            Condition::any()
+                .add(user_totp::Column::LastUsedStep.is_null())
+                .add(user_totp::Column::LastUsedStep.lt(step)),
+        )
+        .exec(&data.db)
+        .await
+        .map_err(db_error)?;
+
+    Ok(result.rows_affected == 1)
+}
+
+// true kalau user punya authenticator TOTP yang sudah dikonfirmasi
+pub(crate) async fn is_enabled(db: &DatabaseConnection, user_id: i32) -> Result<bool, DbErr> {
+    let found = user_totp::Entity::find()
+        .filter(user_totp::Column::UserId.eq(user_id))
+        .filter(user_totp::Column::ConfirmedAt.is_not_null())
+        .one(db)
+        .await?;
+    Ok(found.is_some())
+}
+
+// POST /api/auth/mfa/totp/setup: buat seed baru, aktif setelah dikonfirmasi
+pub async fn setup_totp(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let (claims, user_id) = match extract::require_interactive_user(&req) {
+        Ok(found) => found,
+        Err(resp) => return resp,
+    };
+
+    let secret_box = match secret_box(&data) {
+        Ok(secret_box) => secret_box,
+        Err(resp) => return resp,
+    };
+
+    let existing = match user_totp::Entity::find()
+        .filter(user_totp::Column::UserId.eq(user_id))
+        .one(&data.db)
+        .await
+    {
+        Ok(existing) => existing,
+        Err(err) => {
+            eprintln!("Database error: {:?}", err);
+            return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"));
+        }
+    };
+
+    if existing.as_ref().is_some_and(|t| t.confirmed_at.is_some()) {
+        return HttpResponse::Conflict().json(ApiResponse::<()>::error("TOTP is already enabled"));
+    }
+
+    let secret = totp::generate_secret();
+    let secret_encrypted = secret_box.encrypt(&secret, &secret_context(user_id));
+
+    // Enrollment yang belum dikonfirmasi diganti dengan seed baru
+    let saved = match existing {
+        Some(pending) => {
+            let mut active: user_totp::ActiveModel = pending.into();
+            active.secret_encrypted = Set(secret_encrypted);
+            active.last_used_step = Set(None);
+            active.update(&data.db).await.map(|_| ())
+        }
+        None => user_totp::ActiveModel {
+            user_id: Set(user_id),
+            secret_encrypted: Set(secret_encrypted),
+            ..Default::default()
+        }
+        .insert(&data.db)
+        .await
+        .map(|_| ()),
+    };
+
+    if let Err(err) = saved {
+        eprintln!("Database error: {:?}", err);
+        return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"));
+    }
+
+    let response = TotpSetupResponse {
+        secret: totp::secret_base32(&secret),
+        otpauth_uri: totp::provisioning_uri(&secret, &claims.email),
+    };
+    HttpResponse::Ok().json(ApiResponse::success("Scan the QR code, then confirm with a code from your authenticator", response))
+}
+
+// POST /api/auth/mfa/totp/confirm: aktifkan TOTP dengan kode pertama dari authenticator.
+// Kalau ini faktor kedua pertama user, kode cadangan ikut dibuat dan ditampilkan sekali.
+pub async fn confirm_totp(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    req_body: web::Json<TotpCodeRequest>,
+) -> impl Responder {
+    let (_, user_id) = match extract::require_interactive_user(&req) {
+        Ok(found) => found,
+        Err(resp) => return resp,
+    };
+
+    match verify_user_code(&data, user_id, &req_body.code, false).await {
+        Ok(true) => {}
+        Ok(false) => return HttpResponse::BadRequest().json(ApiResponse::<()>::error("Invalid code")),
+        Err(resp) => return resp,
+    }
+
+    let confirmed = user_totp::Entity::update_many()
+        .col_expr(user_totp::Column::ConfirmedAt, Expr::value(Utc::now()))
+        .filter(user_totp::Column::UserId.eq(user_id))
+        .filter(user_totp::Column::ConfirmedAt.is_null())
+        .exec(&data.db)
+        .await;
+
+    if let Err(err) = confirmed {
+        eprintln!("Database error: {:?}", err);
+        return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"));
+    }
+
+    match recovery_codes::issue_if_missing(&data.db, user_id).await {
+        Ok(Some(recovery_codes)) => HttpResponse::Ok().json(ApiResponse::success(
+            "TOTP enabled, store your recovery codes somewhere safe",
+            RecoveryCodesResponse { recovery_codes },
+        )),
+        Ok(None) => HttpResponse::Ok().json(ApiResponse::<()>::success("TOTP enabled", ())),
+        Err(err) => {
+            eprintln!("Database error: {:?}", err);
+            HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"))
+        }
+    }
+}
+
+// POST /api/auth/mfa/totp/disable: nonaktifkan TOTP, wajib konfirmasi faktor kedua. Selain kode
+// TOTP, kode cadangan atau passkey juga diterima supaya user yang kehilangan authenticator
+// bisa melepas lalu mendaftarkan ulang TOTP
+pub async fn disable_totp(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    req_body: web::Json<SecondFactorProof>,
+) -> impl Responder {
+    let (_, user_id) = match extract::require_interactive_user(&req) {
+        Ok(found) => found,
+        Err(resp) => return resp,
+    };
+
+    match is_enabled(&data.db, user_id).await {
+        Ok(true) => {}
+        Ok(false) => return HttpResponse::BadRequest().json(ApiResponse::<()>::error("TOTP is not enabled")),
+        Err(err) => {
+            eprintln!("Database error: {:?}", err);
+            return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"));
+        }
+    }
+
+    match mfa::verify_proof(&data, &req, user_id, &req_body).await {
+        Ok(true) => {}
+        Ok(false) => return HttpResponse::Unauthorized().json(ApiResponse::<()>::error("Second factor verification failed")),
+        Err(resp) => return resp,
+    }
+
+    let removed = async {
+        user_totp::Entity::delete_many()
+            .filter(user_totp::Column::UserId.eq(user_id))
+            .exec(&data.db)
+            .await?;
+        recovery_codes::clear_if_mfa_disabled(&data.db, user_id).await
+    };
+
+    match removed.await {
+        Ok(()) => HttpResponse::Ok().json(ApiResponse::<()>::success("TOTP disabled", ())),
+        Err(err) => {
+            eprintln!("Database error: {:?}", err);
+            HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"))
+        }
+    }
+}
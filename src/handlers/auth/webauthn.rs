@@ -0,0 +1,583 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{Duration, Utc};
+use sea_orm::*;
+use sea_orm::sea_query::Expr;
+use serde::{Deserialize, Serialize};
+use serde::de::DeserializeOwned;
+use uuid::Uuid;
+use webauthn_rs::prelude::{
+    AuthenticationResult, DiscoverableAuthentication, DiscoverableKey, Passkey, PasskeyAuthentication,
+    PasskeyRegistration, PublicKeyCredential,
+};
+use crate::entity::{user, webauthn_ceremony, webauthn_credential};
+use crate::dtos::common_dto::ApiResponse;
+use crate::dtos::mfa_dto::SecondFactorProof;
+use crate::dtos::webauthn_dto::{
+    FinishPasskeyLoginRequest, FinishRegistrationRequest, FinishWebauthnMfaRequest, RegisteredCredentialResponse,
+    StartWebauthnMfaRequest, WebauthnCeremonyResponse, WebauthnCredentialInfo,
+};
+use crate::middleware::auth_middleware::extract;
+use crate::handlers::user_handler::AppState;
+use super::{mfa, recovery_codes, tokens};
+
+// Batas waktu user menyelesaikan dialog passkey di browser
+const CEREMONY_TTL_SECS: i64 = 5 * 60;
+
+const KIND_REGISTER: &str = "register";
+const KIND_AUTHENTICATE: &str = "authenticate";
+const KIND_DISCOVER: &str = "discover";
+const KIND_REAUTHENTICATE: &str = "reauthenticate";
+
+// State registrasi beserta user handle yang dipakai, supaya finish menyimpan handle yang sama
+#[derive(Serialize, Deserialize)]
+struct RegistrationCeremony {
+    user_handle: Uuid,
+    state: PasskeyRegistration,
+}
+
+fn db_error(err: DbErr) -> HttpResponse {
+    eprintln!("Database error: {:?}", err);
+    HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Database error"))
+}
+
+fn verification_failed(err: impl std::fmt::Debug) -> HttpResponse {
+    eprintln!("WebAuthn error: {:?}", err);
+    HttpResponse::Unauthorized().json(ApiResponse::<()>::error("WebAuthn verification failed"))
+}
+
+// true kalau user punya minimal satu credential WebAuthn
+pub(crate) async fn has_credentials(db: &DatabaseConnection, user_id: i32) -> Result<bool, DbErr> {
+    let count = webauthn_credential::Entity::find()
+        .filter(webauthn_credential::Column::UserId.eq(user_id))
+        .count(db)
+        .await?;
+    Ok(count > 0)
+}
+
+// Credential user beserta Passkey hasil deserialize; baris yang rusak dilewati
+async fn user_passkeys(db: &DatabaseConnection, user_id: i32) -> Result<Vec<(webauthn_credential::Model, Passkey)>, DbErr> {
+    let credentials = webauthn_credential::Entity::find()
+        .filter(webauthn_credential::Column::UserId.eq(user_id))
+        .all(db)
+        .await?;
+
+    Ok(credentials
+        .into_iter()
+        .filter_map(|model| match serde_json::from_str::<Passkey>(&model.passkey) {
+            Ok(passkey) => Some((model, passkey)),
+            Err(err) => {
+                eprintln!("Invalid stored passkey {}: {:?}", model.id, err);
+                None
+            }
+        })
+        .collect())
+}
+
+// Simpan state ceremony, return id yang dikirim ke client
+async fn save_ceremony<T: Serialize>(
+    db: &DatabaseConnection,
+    user_id: Option<i32>,
+    kind: &str,
+    state: &T,
+) -> Result<String, HttpResponse> {
+    let state = serde_json::to_string(state).map_err(|err| {
+        eprintln!("WebAuthn state error: {:?}", err);
+        HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Failed to start WebAuthn ceremony"))
+    })?;
+
+    let now = Utc::now();
+
+    // Ceremony yang ditinggalkan di tengah jalan dibersihkan di sini
+    webauthn_ceremony::Entity::delete_many()
+        .filter(webauthn_ceremony::Column::ExpiresAt.lt(now))
+        .exec(db)
+        .await
+        .map_err(db_error)?;
+
+    let id = Uuid::new_v4().to_string();
+    webauthn_ceremony::ActiveModel {
+        id: Set(id.clone()),
+        user_id: Set(user_id),
+        kind: Set(kind.to_string()),
+        state: Set(state),
+        created_at: NotSet,
+        expires_at: Set(now + Duration::seconds(CEREMONY_TTL_SECS)),
+    }
+    .insert(db)
+    .await
+    .map_err(db_error)?;
+
+    Ok(id)
+}
+
+// Ambil dan hapus state ceremony (sekali pakai). Ceremony harus berjenis `kind`
+// dan milik `user_id` yang sama dengan saat dimulai.
+async fn take_ceremony<T: DeserializeOwned>(
+    db: &DatabaseConnection,
+    ceremony_id: &str,
+    kind: &str,
+    user_id: Option<i32>,
+) -> Result<T, HttpResponse> {
+    let invalid = || HttpResponse::BadRequest().json(ApiResponse::<()>::error("Invalid or expired WebAuthn ceremony"));
+
+    let ceremony = match webauthn_ceremony::Entity::find_by_id(ceremony_id.to_string())
+        .one(db)
+        .await
+        .map_err(db_error)?
+    {
+        Some(ceremony) if ceremony.kind == kind && ceremony.user_id == user_id && ceremony.expires_at > Utc::now() => ceremony,
+        _ => return Err(invalid()),
+    };
+
+    // Request paralel dengan ceremony yang sama hanya satu yang lolos
+    let deleted = webauthn_ceremony::Entity::delete_many()
+        .filter(webauthn_ceremony::Column::Id.eq(ceremony.id.clone()))
+        .exec(db)
+        .await
+        .map_err(db_error)?;
+    if deleted.rows_affected != 1 {
+        return Err(invalid());
+    }
+
+    serde_json::from_str(&ceremony.state).map_err(|err| {
+        eprintln!("WebAuthn state error: {:?}", err);
+        invalid()
+    })
+}
+
+// Catat pemakaian credential: counter & state passkey diperbarui.
+// Update bersyarat pada counter lama, supaya assertion paralel dengan counter yang sama
+// (indikasi authenticator dikloning / replay) hanya satu yang diterima.
+async fn record_use(
+    db: &DatabaseConnection,
+    model: &webauthn_credential::Model,
+    mut passkey: Passkey,
+    result: &AuthenticationResult,
+) -> Result<(), HttpResponse> {
+    passkey.update_credential(result);
+    let passkey = serde_json::to_string(&passkey).map_err(|err| {
+        eprintln!("WebAuthn state error: {:?}", err);
+        HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Failed to update credential"))
+    })?;
+
+    let updated = webauthn_credential::Entity::update_many()
+        .col_expr(webauthn_credential::Column::SignCount, Expr::value(result.counter() as i64))
+        .col_expr(webauthn_credential::Column::Passkey, Expr::value(passkey))
+        .col_expr(webauthn_credential::Column::LastUsedAt, Expr::value(Utc::now()))
+        .filter(webauthn_credential::Column::Id.eq(model.id))
+        .filter(webauthn_credential::Column::SignCount.eq(model.sign_count))
+        .exec(db)
+        .await
+        .map_err(db_error)?;
+
+    if updated.rows_affected != 1 {
+        return Err(verification_failed("signature counter changed concurrently"));
+    }
+    Ok(())
+}
+
+// POST /api/auth/webauthn/register/start: options untuk navigator.credentials.create()
+pub async fn start_registration(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let (claims, user_id) = match extract::require_interactive_user(&req) {
+        Ok(found) => found,
+        Err(resp) => return resp,
+    };
+
+    let user_model = match user::Entity::find_by_id(user_id).one(&data.db).await {
+        Ok(Some(user_model)) => user_model,
+        Ok(None) => return HttpResponse::NotFound().json(ApiResponse::<()>::error("User not found")),
+        Err(err) => return db_error(err),
+    };
+
+    let existing = match user_passkeys(&data.db, user_id).await {
+        Ok(existing) => existing,
+        Err(err) => return db_error(err),
+    };
+
+    // User handle acak, tidak membocorkan id internal. Credential berikutnya memakai handle
+    // yang sama supaya authenticator mengenalinya sebagai akun yang sama.
+    let user_handle = existing
+        .iter()
+        .find_map(|(model, _)| Uuid::parse_str(&model.user_handle).ok())
+        .unwrap_or_else(Uuid::new_v4);
+
+    // Authenticator yang sudah terdaftar tidak boleh didaftarkan dua kali
+    let exclude: Vec<_> = existing.iter().map(|(_, passkey)| passkey.cred_id().clone()).collect();
+
+    let (options, state) = match data.webauthn.start_passkey_registration(
+        user_handle,
+        &claims.email,
+        &user_model.username,
+        Some(exclude),
+    ) {
+        Ok(started) => started,
+        Err(err) => {
+            eprintln!("WebAuthn error: {:?}", err);
+            return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Failed to start WebAuthn ceremony"));
+        }
+    };
+
+    let ceremony = RegistrationCeremony { user_handle, state };
+    match save_ceremony(&data.db, Some(user_id), KIND_REGISTER, &ceremony).await {
+        Ok(ceremony_id) => HttpResponse::Ok().json(ApiResponse::success(
+            "Registration started",
+            WebauthnCeremonyResponse { ceremony_id, options },
+        )),
+        Err(resp) => resp,
+    }
+}
+
+// POST /api/auth/webauthn/register/finish: verifikasi attestation lalu simpan credential
+pub async fn finish_registration(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    req_body: web::Json<FinishRegistrationRequest>,
+) -> impl Responder {
+    let (_, user_id) = match extract::require_interactive_user(&req) {
+        Ok(found) => found,
+        Err(resp) => return resp,
+    };
+
+    let name = req_body.name.as_deref().map(str::trim).filter(|n| !n.is_empty()).unwrap_or("Passkey");
+    if name.len() > 100 {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error("Name must be 1-100 characters"));
+    }
+
+    let ceremony: RegistrationCeremony = match take_ceremony(&data.db, &req_body.ceremony_id, KIND_REGISTER, Some(user_id)).await {
+        Ok(ceremony) => ceremony,
+        Err(resp) => return resp,
+    };
+
+    let passkey = match data.webauthn.finish_passkey_registration(&req_body.credential, &ceremony.state) {
+        Ok(passkey) => passkey,
+        Err(err) => {
+            eprintln!("WebAuthn error: {:?}", err);
+            return HttpResponse::BadRequest().json(ApiResponse::<()>::error("WebAuthn registration failed"));
+        }
+    };
+
+    let credential_id = URL_SAFE_NO_PAD.encode(passkey.cred_id());
+    if credential_id.len() > 512 {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error("Credential id is too long"));
+    }
+
+    let (public_key, serialized) = match (serde_json::to_string(passkey.get_public_key()), serde_json::to_string(&passkey)) {
+        (Ok(public_key), Ok(serialized)) => (public_key, serialized),
+        _ => {
+            eprintln!("Failed to serialize passkey");
+            return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Failed to save credential"));
+        }
+    };
+
+    // Credential id unik: kalau sudah terdaftar (di akun mana pun) insert gagal
+    let new_credential = webauthn_credential::ActiveModel {
+        user_id: Set(user_id),
+        name: Set(name.to_string()),
+        credential_id: Set(credential_id),
+        user_handle: Set(ceremony.user_handle.to_string()),
+        public_key: Set(public_key),
+        sign_count: Set(0),
+        passkey: Set(serialized),
+        ..Default::default()
+    };
+
+    let model = match new_credential.insert(&data.db).await {
+        Ok(model) => model,
+        Err(err) if matches!(err.sql_err(), Some(SqlErr::UniqueConstraintViolation(_))) => {
+            return HttpResponse::Conflict().json(ApiResponse::<()>::error("Credential is already registered"));
+        }
+        Err(err) => return db_error(err),
+    };
+
+    // Faktor kedua pertama: sertakan kode cadangan, hanya ditampilkan sekali ini
+    match recovery_codes::issue_if_missing(&data.db, user_id).await {
+        Ok(recovery_codes) => {
+            let response = RegisteredCredentialResponse {
+                credential: WebauthnCredentialInfo::from(model),
+                recovery_codes,
+            };
+            HttpResponse::Created().json(ApiResponse::success("Credential registered", response))
+        }
+        Err(err) => db_error(err),
+    }
+}
+
+// GET /api/auth/webauthn/credentials
+pub async fn list_credentials(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let (_, user_id) = match extract::require_user(&req) {
+        Ok(found) => found,
+        Err(resp) => return resp,
+    };
+
+    match webauthn_credential::Entity::find()
+        .filter(webauthn_credential::Column::UserId.eq(user_id))
+        .order_by_desc(webauthn_credential::Column::CreatedAt)
+        .all(&data.db)
+        .await
+    {
+        Ok(credentials) => {
+            let credentials: Vec<WebauthnCredentialInfo> = credentials.into_iter().map(WebauthnCredentialInfo::from).collect();
+            HttpResponse::Ok().json(ApiResponse::success("Credentials retrieved", credentials))
+        }
+        Err(err) => db_error(err),
+    }
+}
+
+// DELETE /api/auth/webauthn/credentials/{id}: wajib konfirmasi ulang faktor kedua di body
+pub async fn delete_credential(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i32>,
+    req_body: web::Json<SecondFactorProof>,
+) -> impl Responder {
+    let (_, user_id) = match extract::require_interactive_user(&req) {
+        Ok(found) => found,
+        Err(resp) => return resp,
+    };
+
+    match mfa::verify_proof(&data, &req, user_id, &req_body).await {
+        Ok(true) => {}
+        Ok(false) => return HttpResponse::Unauthorized().json(ApiResponse::<()>::error("Second factor verification failed")),
+        Err(resp) => return resp,
+    }
+
+    match webauthn_credential::Entity::delete_many()
+        .filter(webauthn_credential::Column::Id.eq(path.into_inner()))
+        .filter(webauthn_credential::Column::UserId.eq(user_id))
+        .exec(&data.db)
+        .await
+    {
+        Ok(result) if result.rows_affected > 0 => {}
+        Ok(_) => return HttpResponse::NotFound().json(ApiResponse::<()>::error("Credential not found")),
+        Err(err) => return db_error(err),
+    }
+
+    match recovery_codes::clear_if_mfa_disabled(&data.db, user_id).await {
+        Ok(()) => HttpResponse::Ok().json(ApiResponse::<()>::success("Credential removed", ())),
+        Err(err) => db_error(err),
+    }
+}
+
+// POST /api/auth/webauthn/reauth/start: assertion untuk konfirmasi ulang aksi sensitif
+// (mis. menghapus passkey) oleh user yang sudah login
+pub async fn start_reauthentication(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let (_, user_id) = match extract::require_interactive_user(&req) {
+        Ok(found) => found,
+        Err(resp) => return resp,
+    };
+
+    let passkeys: Vec<Passkey> = match user_passkeys(&data.db, user_id).await {
+        Ok(found) => found.into_iter().map(|(_, passkey)| passkey).collect(),
+        Err(err) => return db_error(err),
+    };
+    if passkeys.is_empty() {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error("No WebAuthn credentials registered"));
+    }
+
+    let (options, state) = match data.webauthn.start_passkey_authentication(&passkeys) {
+        Ok(started) => started,
+        Err(err) => {
+            eprintln!("WebAuthn error: {:?}", err);
+            return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Failed to start WebAuthn ceremony"));
+        }
+    };
+
+    match save_ceremony::<PasskeyAuthentication>(&data.db, Some(user_id), KIND_REAUTHENTICATE, &state).await {
+        Ok(ceremony_id) => HttpResponse::Ok().json(ApiResponse::success(
+            "Authentication started",
+            WebauthnCeremonyResponse { ceremony_id, options },
+        )),
+        Err(resp) => resp,
+    }
+}
+
+// Verifikasi assertion dari ceremony reauth milik user ini
+pub(crate) async fn verify_reauthentication(
+    data: &AppState,
+    user_id: i32,
+    ceremony_id: &str,
+    credential: &PublicKeyCredential,
+) -> Result<bool, HttpResponse> {
+    let state: PasskeyAuthentication = take_ceremony(&data.db, ceremony_id, KIND_REAUTHENTICATE, Some(user_id)).await?;
+
+    let result = match data.webauthn.finish_passkey_authentication(credential, &state) {
+        Ok(result) => result,
+        Err(err) => {
+            eprintln!("WebAuthn error: {:?}", err);
+            return Ok(false);
+        }
+    };
+
+    let passkeys = user_passkeys(&data.db, user_id).await.map_err(db_error)?;
+    let Some((model, passkey)) = passkeys.into_iter().find(|(_, passkey)| passkey.cred_id() == result.cred_id()) else {
+        return Ok(false);
+    };
+
+    record_use(&data.db, &model, passkey, &result).await?;
+    Ok(true)
+}
+
+// POST /api/auth/webauthn/mfa/start: WebAuthn sebagai faktor kedua setelah challenge MFA
+pub async fn start_mfa(
+    data: web::Data<AppState>,
+    req_body: web::Json<StartWebauthnMfaRequest>,
+) -> impl Responder {
+    let challenge = match mfa::find_active(&data.db, &req_body.challenge_token).await {
+        Ok(challenge) => challenge,
+        Err(resp) => return resp,
+    };
+
+    let passkeys: Vec<Passkey> = match user_passkeys(&data.db, challenge.user_id).await {
+        Ok(found) => found.into_iter().map(|(_, passkey)| passkey).collect(),
+        Err(err) => return db_error(err),
+    };
+    if passkeys.is_empty() {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error("No WebAuthn credentials registered"));
+    }
+
+    let (options, state) = match data.webauthn.start_passkey_authentication(&passkeys) {
+        Ok(started) => started,
+        Err(err) => {
+            eprintln!("WebAuthn error: {:?}", err);
+            return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Failed to start WebAuthn ceremony"));
+        }
+    };
+
+    match save_ceremony::<PasskeyAuthentication>(&data.db, Some(challenge.user_id), KIND_AUTHENTICATE, &state).await {
+        Ok(ceremony_id) => HttpResponse::Ok().json(ApiResponse::success(
+            "Authentication started",
+            WebauthnCeremonyResponse { ceremony_id, options },
+        )),
+        Err(resp) => resp,
+    }
+}
+
+// POST /api/auth/webauthn/mfa/finish: verifikasi assertion lalu terbitkan token
+pub async fn finish_mfa(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    req_body: web::Json<FinishWebauthnMfaRequest>,
+) -> impl Responder {
+    let challenge = match mfa::start_attempt(&data.db, &req_body.challenge_token).await {
+        Ok(challenge) => challenge,
+        Err(resp) => return resp,
+    };
+
+    let state: PasskeyAuthentication =
+        match take_ceremony(&data.db, &req_body.ceremony_id, KIND_AUTHENTICATE, Some(challenge.user_id)).await {
+            Ok(state) => state,
+            Err(resp) => return resp,
+        };
+
+    let result = match data.webauthn.finish_passkey_authentication(&req_body.credential, &state) {
+        Ok(result) => result,
+        Err(err) => return verification_failed(err),
+    };
+
+    let passkeys = match user_passkeys(&data.db, challenge.user_id).await {
+        Ok(passkeys) => passkeys,
+        Err(err) => return db_error(err),
+    };
+    let Some((model, passkey)) = passkeys.into_iter().find(|(_, passkey)| passkey.cred_id() == result.cred_id()) else {
+        return verification_failed("credential not found");
+    };
+
+    if let Err(resp) = record_use(&data.db, &model, passkey, &result).await {
+        return resp;
+    }
+
+    mfa::finish(&data, &req, challenge).await
+}
+
+// POST /api/auth/webauthn/login/start: login passkey tanpa username, authenticator yang memilih akun
+pub async fn start_passkey_login(
+    data: web::Data<AppState>,
+) -> impl Responder {
+    let (options, state) = match data.webauthn.start_discoverable_authentication() {
+        Ok(started) => started,
+        Err(err) => {
+            eprintln!("WebAuthn error: {:?}", err);
+            return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Failed to start WebAuthn ceremony"));
+        }
+    };
+
+    match save_ceremony::<DiscoverableAuthentication>(&data.db, None, KIND_DISCOVER, &state).await {
+        Ok(ceremony_id) => HttpResponse::Ok().json(ApiResponse::success(
+            "Authentication started",
+            WebauthnCeremonyResponse { ceremony_id, options },
+        )),
+        Err(resp) => resp,
+    }
+}
+
+// POST /api/auth/webauthn/login/finish: passkey dengan user verification sudah dua faktor
+// (perangkat + PIN/biometrik), jadi token langsung diterbitkan tanpa challenge MFA
+pub async fn finish_passkey_login(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    req_body: web::Json<FinishPasskeyLoginRequest>,
+) -> impl Responder {
+    let state: DiscoverableAuthentication = match take_ceremony(&data.db, &req_body.ceremony_id, KIND_DISCOVER, None).await {
+        Ok(state) => state,
+        Err(resp) => return resp,
+    };
+
+    let (handle, credential_id) = match data.webauthn.identify_discoverable_authentication(&req_body.credential) {
+        Ok(identified) => identified,
+        Err(err) => return verification_failed(err),
+    };
+
+    let model = match webauthn_credential::Entity::find()
+        .filter(webauthn_credential::Column::CredentialId.eq(URL_SAFE_NO_PAD.encode(credential_id)))
+        .one(&data.db)
+        .await
+    {
+        Ok(Some(model)) => model,
+        Ok(None) => return verification_failed("unknown credential"),
+        Err(err) => return db_error(err),
+    };
+
+    // User handle dari authenticator harus sama dengan yang tersimpan saat registrasi
+    if Uuid::parse_str(&model.user_handle).ok() != Some(handle) {
+        return verification_failed("user handle mismatch");
+    }
+
+    let passkey: Passkey = match serde_json::from_str(&model.passkey) {
+        Ok(passkey) => passkey,
+        Err(err) => return verification_failed(err),
+    };
+
+    let result = match data.webauthn.finish_discoverable_authentication(
+        &req_body.credential,
+        state,
+        &[DiscoverableKey::from(&passkey)],
+    ) {
+        Ok(result) => result,
+        Err(err) => return verification_failed(err),
+    };
+
+    if let Err(resp) = record_use(&data.db, &model, passkey, &result).await {
+        return resp;
+    }
+
+    match user::Entity::find_by_id(model.user_id).one(&data.db).await {
+        Ok(Some(user_model)) => {
+            tokens::issue_tokens(&data, &req, user_model, None, req_body.remember_me, "Login successful").await
+        }
+        Ok(None) => verification_failed("user not found"),
+        Err(err) => db_error(err),
+    }
+}
+
+#[cfg(test)]
+mod tests;
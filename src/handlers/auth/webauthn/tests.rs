@@ -0,0 +1,344 @@
+// Ceremony WebAuthn end-to-end lewat route asli, dengan software authenticator
+// (webauthn-authenticator-rs) dan SQLite in-memory sebagai pengganti MySQL.
+use actix_web::{test, web, App};
+use async_trait::async_trait;
+use chrono::Duration;
+use sea_orm::{ConnectionTrait, Database, DatabaseConnection, EntityTrait, Schema, Set, NotSet, ActiveModelTrait};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use uuid::Uuid;
+use webauthn_authenticator_rs::WebauthnAuthenticator;
+use webauthn_authenticator_rs::softpasskey::SoftPasskey;
+use webauthn_rs::prelude::{CreationChallengeResponse, RequestChallengeResponse, Url};
+use crate::config::auth::AuthMode;
+use crate::entity::{
+    mfa_challenge, mfa_recovery_code, refresh_token, revoked_token, server_session, user, user_session, user_totp,
+    webauthn_ceremony, webauthn_credential,
+};
+use crate::handlers::user_handler::AppState;
+use crate::routes;
+use crate::utils::auth_provider::AuthProviderChain;
+use crate::utils::mailer::{Email, MailError, Mailer};
+use crate::utils::otp::OtpChannels;
+use crate::utils::session_revocation::SessionRevocationCache;
+use crate::utils::token_version::TokenVersionCache;
+use crate::utils::{denylist, jwt, session_store, webauthn as webauthn_config};
+use super::super::mfa;
+
+// Origin default WEBAUTHN_RP_ORIGIN
+const ORIGIN: &str = "http://localhost:8080";
+
+struct NullMailer;
+
+#[async_trait]
+impl Mailer for NullMailer {
+    async fn send(&self, _email: &Email) -> Result<(), MailError> {
+        Ok(())
+    }
+}
+
+async fn setup_db() -> DatabaseConnection {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let schema = Schema::new(db.get_database_backend());
+    let backend = db.get_database_backend();
+
+    macro_rules! create {
+        ($($entity:path),* $(,)?) => {
+            $(db.execute(backend.build(&schema.create_table_from_entity($entity))).await.unwrap();)*
+        };
+    }
+    create!(
+        user::Entity,
+        revoked_token::Entity,
+        server_session::Entity,
+        user_session::Entity,
+        refresh_token::Entity,
+        mfa_challenge::Entity,
+        mfa_recovery_code::Entity,
+        user_totp::Entity,
+        webauthn_credential::Entity,
+        webauthn_ceremony::Entity,
+    );
+    db
+}
+
+fn app_state(db: &DatabaseConnection) -> web::Data<AppState> {
+    let mailer: Arc<dyn Mailer> = Arc::new(NullMailer);
+    web::Data::new(AppState {
+        db: db.clone(),
+        denylist: denylist::from_env(db),
+        token_versions: TokenVersionCache::new(std::time::Duration::from_secs(0)),
+        revoked_sessions: SessionRevocationCache::new(std::time::Duration::from_secs(0)),
+        auth_mode: AuthMode::Jwt,
+        sessions: session_store::from_env(db),
+        oidc: None,
+        auth_providers: AuthProviderChain::from_env(db),
+        mailer: mailer.clone(),
+        otp_channels: OtpChannels::from_env(mailer),
+        secret_box: None,
+        webauthn: webauthn_config::from_env(),
+    })
+}
+
+async fn create_user(db: &DatabaseConnection) -> (user::Model, String) {
+    let user_model = user::ActiveModel {
+        id: NotSet,
+        username: Set("alice".to_string()),
+        email: Set("alice@example.com".to_string()),
+        phone: Set(None),
+        phone_verified_at: Set(None),
+        password_hash: Set(String::new()),
+        role: Set("user".to_string()),
+        token_version: Set(0),
+        created_at: NotSet,
+        updated_at: NotSet,
+    }
+    .insert(db)
+    .await
+    .unwrap();
+
+    let claims = jwt::Claims::new_access_token(
+        user_model.id,
+        user_model.email.clone(),
+        user_model.role.clone(),
+        user_model.token_version,
+        Duration::minutes(5),
+    );
+    (user_model, jwt::encode_claims(&claims).unwrap())
+}
+
+fn authenticator() -> WebauthnAuthenticator<SoftPasskey> {
+    // SoftPasskey tidak punya PIN/biometrik, user verification dianggap lolos
+    WebauthnAuthenticator::new(SoftPasskey::new(true))
+}
+
+fn origin() -> Url {
+    Url::parse(ORIGIN).unwrap()
+}
+
+macro_rules! call {
+    ($app:expr, $req:expr) => {{
+        let resp = test::call_service(&$app, $req.to_request()).await;
+        let status = resp.status().as_u16();
+        let body: Value = test::read_body_json(resp).await;
+        (status, body)
+    }};
+}
+
+macro_rules! init_app {
+    ($state:expr) => {
+        test::init_service(App::new().app_data($state.clone()).configure(routes::config)).await
+    };
+}
+
+// Daftarkan passkey baru lewat register/start + register/finish, return (status, body finish)
+macro_rules! register {
+    ($app:expr, $token:expr, $auth:expr) => {{
+        let (status, started) = call!(
+            $app,
+            test::TestRequest::post()
+                .uri("/api/auth/webauthn/register/start")
+                .insert_header(("Authorization", format!("Bearer {}", $token)))
+        );
+        assert_eq!(status, 200, "{}", started);
+        let options: CreationChallengeResponse = serde_json::from_value(started["data"]["options"].clone()).unwrap();
+        let credential = $auth.do_registration(origin(), options).unwrap();
+
+        call!(
+            $app,
+            test::TestRequest::post()
+                .uri("/api/auth/webauthn/register/finish")
+                .insert_header(("Authorization", format!("Bearer {}", $token)))
+                .set_json(json!({
+                    "ceremony_id": started["data"]["ceremony_id"],
+                    "name": "Test key",
+                    "credential": credential,
+                }))
+        )
+    }};
+}
+
+#[actix_web::test]
+async fn registers_passkey_with_random_user_handle_and_recovery_codes() {
+    let db = setup_db().await;
+    let state = app_state(&db);
+    let app = init_app!(state);
+    let (user_model, token) = create_user(&db).await;
+    let mut auth = authenticator();
+
+    let (status, body) = register!(app, token, auth);
+    assert_eq!(status, 201, "{}", body);
+    assert_eq!(body["data"]["name"], "Test key");
+    assert_eq!(body["data"]["recovery_codes"].as_array().map(Vec::len), Some(10));
+
+    let stored = webauthn_credential::Entity::find().one(&db).await.unwrap().unwrap();
+    assert_eq!(stored.user_id, user_model.id);
+    let handle = Uuid::parse_str(&stored.user_handle).unwrap();
+    assert_ne!(handle, Uuid::from_u128(user_model.id as u128));
+
+    // Passkey kedua: handle sama, kode cadangan tidak dibuat ulang
+    let mut second = authenticator();
+    let (status, body) = register!(app, token, second);
+    assert_eq!(status, 201, "{}", body);
+    assert!(body["data"].get("recovery_codes").is_none());
+    let handles: Vec<String> = webauthn_credential::Entity::find()
+        .all(&db)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|c| c.user_handle)
+        .collect();
+    assert_eq!(handles, vec![stored.user_handle.clone(), stored.user_handle]);
+}
+
+#[actix_web::test]
+async fn passkey_completes_mfa_challenge() {
+    let db = setup_db().await;
+    let state = app_state(&db);
+    let app = init_app!(state);
+    let (user_model, token) = create_user(&db).await;
+    let mut auth = authenticator();
+
+    let (status, body) = register!(app, token, auth);
+    assert_eq!(status, 201, "{}", body);
+
+    // Faktor pertama selesai: user dengan passkey mendapat challenge, bukan token
+    let req = test::TestRequest::default().to_http_request();
+    let resp = mfa::complete_login(&state, &req, user_model, false).await;
+    let challenge: Value = serde_json::from_slice(&actix_web::body::to_bytes(resp.into_body()).await.unwrap()).unwrap();
+    assert_eq!(challenge["data"]["mfa_required"], true);
+    let methods = challenge["data"]["methods"].as_array().unwrap();
+    assert!(methods.contains(&json!("webauthn")));
+    assert!(methods.contains(&json!("recovery_code")));
+    let challenge_token = challenge["data"]["challenge_token"].clone();
+
+    let (status, started) = call!(
+        app,
+        test::TestRequest::post()
+            .uri("/api/auth/webauthn/mfa/start")
+            .set_json(json!({ "challenge_token": challenge_token }))
+    );
+    assert_eq!(status, 200, "{}", started);
+    let options: RequestChallengeResponse = serde_json::from_value(started["data"]["options"].clone()).unwrap();
+    let assertion = auth.do_authentication(origin(), options).unwrap();
+
+    let finish = json!({
+        "challenge_token": challenge_token,
+        "ceremony_id": started["data"]["ceremony_id"],
+        "credential": assertion,
+    });
+    let (status, body) = call!(
+        app,
+        test::TestRequest::post().uri("/api/auth/webauthn/mfa/finish").set_json(&finish)
+    );
+    assert_eq!(status, 200, "{}", body);
+    assert!(body["data"]["access_token"].is_string());
+
+    // Challenge dan ceremony sekali pakai: assertion yang sama tidak bisa diulang
+    let (status, _) = call!(
+        app,
+        test::TestRequest::post().uri("/api/auth/webauthn/mfa/finish").set_json(&finish)
+    );
+    assert_eq!(status, 401);
+}
+
+#[actix_web::test]
+async fn passkey_signs_in_without_username() {
+    let db = setup_db().await;
+    let state = app_state(&db);
+    let app = init_app!(state);
+    let (_, token) = create_user(&db).await;
+    let mut auth = authenticator();
+
+    let (status, body) = register!(app, token, auth);
+    assert_eq!(status, 201, "{}", body);
+    let stored = webauthn_credential::Entity::find().one(&db).await.unwrap().unwrap();
+    let handle = Uuid::parse_str(&stored.user_handle).unwrap();
+
+    // SoftPasskey tidak mendukung resident key, jadi peran authenticator platform ditiru:
+    // pilih credential yang tersimpan dan kembalikan user handle-nya di assertion
+    let sign_in = |auth: &mut WebauthnAuthenticator<SoftPasskey>, started: &Value, user_handle: Uuid| {
+        let mut options: RequestChallengeResponse = serde_json::from_value(started["data"]["options"].clone()).unwrap();
+        options.public_key.allow_credentials =
+            serde_json::from_value(json!([{ "type": "public-key", "id": stored.credential_id }])).unwrap();
+        let mut assertion = auth.do_authentication(origin(), options).unwrap();
+        assertion.response.user_handle = Some(user_handle.as_bytes().to_vec().into());
+        json!({
+            "ceremony_id": started["data"]["ceremony_id"],
+            "credential": assertion,
+        })
+    };
+
+    // User handle yang tidak cocok dengan credential ditolak
+    let (_, started) = call!(app, test::TestRequest::post().uri("/api/auth/webauthn/login/start"));
+    let (status, _) = call!(
+        app,
+        test::TestRequest::post()
+            .uri("/api/auth/webauthn/login/finish")
+            .set_json(sign_in(&mut auth, &started, Uuid::new_v4()))
+    );
+    assert_eq!(status, 401);
+
+    let (status, started) = call!(app, test::TestRequest::post().uri("/api/auth/webauthn/login/start"));
+    assert_eq!(status, 200, "{}", started);
+    let (status, body) = call!(
+        app,
+        test::TestRequest::post()
+            .uri("/api/auth/webauthn/login/finish")
+            .set_json(sign_in(&mut auth, &started, handle))
+    );
+    assert_eq!(status, 200, "{}", body);
+    assert!(body["data"]["access_token"].is_string());
+
+    let used = webauthn_credential::Entity::find_by_id(stored.id).one(&db).await.unwrap().unwrap();
+    assert!(used.last_used_at.is_some());
+}
+
+#[actix_web::test]
+async fn deleting_passkey_requires_fresh_assertion() {
+    let db = setup_db().await;
+    let state = app_state(&db);
+    let app = init_app!(state);
+    let (_, token) = create_user(&db).await;
+    let mut auth = authenticator();
+
+    let (status, body) = register!(app, token, auth);
+    assert_eq!(status, 201, "{}", body);
+    let id = body["data"]["id"].as_i64().unwrap();
+    let uri = format!("/api/auth/webauthn/credentials/{}", id);
+
+    // Access token saja tidak cukup
+    let (status, _) = call!(
+        app,
+        test::TestRequest::delete()
+            .uri(&uri)
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(json!({}))
+    );
+    assert_eq!(status, 400);
+
+    let (status, started) = call!(
+        app,
+        test::TestRequest::post()
+            .uri("/api/auth/webauthn/reauth/start")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+    );
+    assert_eq!(status, 200, "{}", started);
+    let options: RequestChallengeResponse = serde_json::from_value(started["data"]["options"].clone()).unwrap();
+    let assertion = auth.do_authentication(origin(), options).unwrap();
+
+    let (status, body) = call!(
+        app,
+        test::TestRequest::delete()
+            .uri(&uri)
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(json!({
+                "ceremony_id": started["data"]["ceremony_id"],
+                "credential": assertion,
+            }))
+    );
+    assert_eq!(status, 200, "{}", body);
+    assert!(webauthn_credential::Entity::find().one(&db).await.unwrap().is_none());
+    // Faktor kedua terakhir dilepas: kode cadangan ikut dihapus
+    assert!(mfa_recovery_code::Entity::find().one(&db).await.unwrap().is_none());
+}
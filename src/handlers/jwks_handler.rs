@@ -0,0 +1,13 @@
+use actix_web::{HttpResponse, Responder};
+use jsonwebtoken::jwk::JwkSet;
+use crate::utils::keys;
+
+// Public key untuk verifikasi JWT oleh service lain (RFC 7517).
+// Key HS256 tidak ikut dipublish karena secret tidak boleh dibagikan.
+pub async fn jwks() -> impl Responder {
+    let keys = keys::public_jwks();
+
+    HttpResponse::Ok()
+        .insert_header(("Cache-Control", "public, max-age=300"))
+        .json(JwkSet { keys })
+}
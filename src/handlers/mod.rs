@@ -1,2 +1,5 @@
 pub mod user_handler;
-pub mod auth;
\ No newline at end of file
+pub mod auth;
+pub mod admin;
+pub mod jwks_handler;
+pub mod oauth;
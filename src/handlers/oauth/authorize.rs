@@ -0,0 +1,295 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder, http::StatusCode};
+use chrono::{Duration, Utc};
+use sea_orm::*;
+use crate::config::{auth, cookie};
+use crate::entity::{oauth_authorization_code, oauth_client};
+use crate::dtos::oauth_dto::AuthorizeRequest;
+use crate::middleware::auth_middleware::extract;
+use crate::middleware::csrf;
+use crate::handlers::user_handler::AppState;
+use crate::utils::jwt::Claims;
+use crate::utils::{hash, pkce, scope};
+
+// Request authorize yang sudah lolos validasi
+struct ValidAuthorization {
+    client: oauth_client::Model,
+    redirect_uri: String,
+    scopes: Vec<String>,
+    state: Option<String>,
+    code_challenge: String,
+}
+
+enum AuthorizeError {
+    // client_id / redirect_uri tidak valid: jangan redirect, tampilkan error ke user
+    Page(StatusCode, &'static str),
+    // Error lain dikirim balik ke client lewat redirect_uri
+    Redirect {
+        redirect_uri: String,
+        state: Option<String>,
+        error: &'static str,
+        description: &'static str,
+    },
+    Database(DbErr),
+}
+
+impl AuthorizeError {
+    fn into_response(self) -> HttpResponse {
+        match self {
+            AuthorizeError::Page(status, message) => error_page(status, message),
+            AuthorizeError::Redirect { redirect_uri, state, error, description } => {
+                let mut params = vec![("error", error), ("error_description", description)];
+                if let Some(state) = state.as_deref() {
+                    params.push(("state", state));
+                }
+                redirect(&redirect_uri, &params)
+            }
+            AuthorizeError::Database(err) => {
+                eprintln!("Database error: {:?}", err);
+                error_page(StatusCode::INTERNAL_SERVER_ERROR, "Something went wrong, please try again")
+            }
+        }
+    }
+}
+
+// Validasi parameter authorize, dipakai oleh GET (tampilkan consent) dan POST (keputusan user)
+async fn validate(
+    data: &AppState,
+    claims: &Claims,
+    params: &AuthorizeRequest,
+) -> Result<ValidAuthorization, AuthorizeError> {
+    // Hanya login interaktif biasa yang boleh memberi izin ke aplikasi lain
+    if claims.token_type != "access" || claims.scopes.is_some() || claims.is_impersonated() {
+        return Err(AuthorizeError::Page(StatusCode::FORBIDDEN, "Please sign in directly to authorize applications"));
+    }
+
+    let client_id = params.client_id.as_deref().unwrap_or_default();
+    let client = match oauth_client::Entity::find_by_id(client_id.to_string()).one(&data.db).await {
+        Ok(Some(client)) if !client.service_account => client,
+        Ok(_) => return Err(AuthorizeError::Page(StatusCode::BAD_REQUEST, "Unknown client")),
+        Err(err) => return Err(AuthorizeError::Database(err)),
+    };
+
+    let redirect_uri = params.redirect_uri.clone().unwrap_or_default();
+    if !client.allows_redirect_uri(&redirect_uri) {
+        return Err(AuthorizeError::Page(StatusCode::BAD_REQUEST, "Invalid redirect_uri"));
+    }
+
+    // Mulai dari sini error boleh dikirim ke redirect_uri
+    let state = params.state.clone();
+    let fail = |error, description| AuthorizeError::Redirect {
+        redirect_uri: redirect_uri.clone(),
+        state: state.clone(),
+        error,
+        description,
+    };
+
+    if params.response_type.as_deref() != Some("code") {
+        return Err(fail("unsupported_response_type", "Only response_type=code is supported"));
+    }
+
+    // PKCE wajib untuk semua client
+    let code_challenge = match params.code_challenge.as_deref() {
+        Some(challenge) if pkce::is_valid_challenge(challenge) => challenge.to_string(),
+        _ => return Err(fail("invalid_request", "A valid code_challenge is required")),
+    };
+    if params.code_challenge_method.as_deref() != Some(pkce::METHOD_S256) {
+        return Err(fail("invalid_request", "code_challenge_method must be S256"));
+    }
+
+    // Tanpa parameter scope, client mendapat semua scope yang diizinkan untuknya
+    let allowed = scope::parse(&client.scopes);
+    let scopes = match params.scope.as_deref() {
+        Some(requested) => scope::parse(requested),
+        None => allowed.clone(),
+    };
+    if scopes.is_empty() || !scope::is_subset(&scopes, &allowed) {
+        return Err(fail("invalid_scope", "Requested scope is not allowed for this client"));
+    }
+    if scopes.iter().any(|s| s == scope::ADMIN) && claims.role != "admin" {
+        return Err(fail("invalid_scope", "Admin scope requires an admin account"));
+    }
+
+    Ok(ValidAuthorization {
+        client,
+        redirect_uri,
+        scopes,
+        state,
+        code_challenge,
+    })
+}
+
+// GET /oauth/authorize: tampilkan halaman consent
+pub async fn authorize(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    query: web::Query<AuthorizeRequest>,
+) -> impl Responder {
+    let claims = match extract::get_claims(&req) {
+        Ok(claims) => claims,
+        Err(resp) => return resp,
+    };
+
+    let valid = match validate(&data, &claims, &query).await {
+        Ok(valid) => valid,
+        Err(err) => return err.into_response(),
+    };
+
+    // Form consent butuh CSRF token, buat cookie baru kalau belum ada
+    let (csrf_token, new_cookie) = match cookie::get(&req, cookie::CSRF_TOKEN) {
+        Some(token) => (token, None),
+        None => {
+            let csrf_cookie = csrf::new_cookie(None);
+            (csrf_cookie.value().to_string(), Some(csrf_cookie))
+        }
+    };
+
+    let mut response = html_response(StatusCode::OK, consent_page(&valid, &claims.email, &csrf_token));
+    if let Some(csrf_cookie) = new_cookie
+        && let Err(err) = response.add_cookie(&csrf_cookie)
+    {
+        eprintln!("Cookie error: {:?}", err);
+    }
+    response
+}
+
+// POST /oauth/authorize: user menyetujui atau menolak, lalu redirect ke client
+pub async fn authorize_decision(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    form: web::Form<AuthorizeRequest>,
+) -> impl Responder {
+    let claims = match extract::get_claims(&req) {
+        Ok(claims) => claims,
+        Err(resp) => return resp,
+    };
+
+    if !csrf::verify_form(&req, form.csrf_token.as_deref()) {
+        return error_page(StatusCode::FORBIDDEN, "Invalid or missing CSRF token");
+    }
+
+    let valid = match validate(&data, &claims, &form).await {
+        Ok(valid) => valid,
+        Err(err) => return err.into_response(),
+    };
+
+    let mut params: Vec<(&str, &str)> = Vec::new();
+    if form.decision.as_deref() != Some("approve") {
+        params.push(("error", "access_denied"));
+        params.push(("error_description", "The user denied the request"));
+        if let Some(state) = valid.state.as_deref() {
+            params.push(("state", state));
+        }
+        return redirect(&valid.redirect_uri, &params);
+    }
+
+    let Some(user_id) = claims.user_id() else {
+        return error_page(StatusCode::UNAUTHORIZED, "Unauthorized");
+    };
+
+    let code = hash::random_token(32);
+    let new_code = oauth_authorization_code::ActiveModel {
+        code_hash: Set(hash::sha256_hex(&code)),
+        client_id: Set(valid.client.id.clone()),
+        user_id: Set(user_id),
+        redirect_uri: Set(valid.redirect_uri.clone()),
+        scopes: Set(scope::join(&valid.scopes)),
+        code_challenge: Set(valid.code_challenge.clone()),
+        expires_at: Set(Utc::now() + Duration::seconds(auth::oauth_code_ttl_secs())),
+        ..Default::default()
+    };
+
+    if let Err(err) = new_code.insert(&data.db).await {
+        return AuthorizeError::Database(err).into_response();
+    }
+
+    params.push(("code", &code));
+    if let Some(state) = valid.state.as_deref() {
+        params.push(("state", state));
+    }
+    redirect(&valid.redirect_uri, &params)
+}
+
+fn redirect(redirect_uri: &str, params: &[(&str, &str)]) -> HttpResponse {
+    let separator = if redirect_uri.contains('?') { '&' } else { '?' };
+    let query = serde_urlencoded::to_string(params).unwrap_or_default();
+    HttpResponse::Found()
+        .insert_header(("Location", format!("{}{}{}", redirect_uri, separator, query)))
+        .insert_header(("Cache-Control", "no-store"))
+        .finish()
+}
+
+// Halaman consent tidak boleh di-embed di iframe (clickjacking)
+fn html_response(status: StatusCode, body: String) -> HttpResponse {
+    HttpResponse::build(status)
+        .content_type("text/html; charset=utf-8")
+        .insert_header(("X-Frame-Options", "DENY"))
+        .insert_header(("Content-Security-Policy", "default-src 'none'; style-src 'unsafe-inline'; form-action 'self'; frame-ancestors 'none'"))
+        .insert_header(("Cache-Control", "no-store"))
+        .body(body)
+}
+
+fn error_page(status: StatusCode, message: &str) -> HttpResponse {
+    html_response(
+        status,
+        format!(
+            "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>Authorization error</title></head>\
+             <body><h1>Authorization error</h1><p>{}</p></body></html>",
+            escape_html(message)
+        ),
+    )
+}
+
+fn consent_page(valid: &ValidAuthorization, email: &str, csrf_token: &str) -> String {
+    let hidden = |name: &str, value: &str| {
+        format!("<input type=\"hidden\" name=\"{}\" value=\"{}\">", name, escape_html(value))
+    };
+    let scope_items: String = valid
+        .scopes
+        .iter()
+        .map(|s| format!("<li><code>{}</code></li>", escape_html(s)))
+        .collect();
+    let scope_value = scope::join(&valid.scopes);
+
+    let mut fields = vec![
+        hidden("response_type", "code"),
+        hidden("client_id", &valid.client.id),
+        hidden("redirect_uri", &valid.redirect_uri),
+        hidden("scope", &scope_value),
+        hidden("code_challenge", &valid.code_challenge),
+        hidden("code_challenge_method", pkce::METHOD_S256),
+        hidden("csrf_token", csrf_token),
+    ];
+    if let Some(state) = valid.state.as_deref() {
+        fields.push(hidden("state", state));
+    }
+
+    format!(
+        "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>Authorize {name}</title></head><body>\
+         <h1>Authorize {name}</h1>\
+         <p>Signed in as <strong>{email}</strong>. <strong>{name}</strong> is requesting access to:</p>\
+         <ul>{scopes}</ul>\
+         <form method=\"post\" action=\"/oauth/authorize\">{fields}\
+         <button type=\"submit\" name=\"decision\" value=\"approve\">Allow</button> \
+         <button type=\"submit\" name=\"decision\" value=\"deny\">Deny</button>\
+         </form></body></html>",
+        name = escape_html(&valid.client.name),
+        email = escape_html(email),
+        scopes = scope_items,
+        fields = fields.concat(),
+    )
+}
+
+fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#x27;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
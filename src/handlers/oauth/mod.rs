@@ -0,0 +1,6 @@
+pub mod authorize;
+pub mod token;
+
+// Re-export untuk kemudahan akses
+pub use authorize::{authorize, authorize_decision};
+pub use token::token;
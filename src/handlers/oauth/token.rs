@@ -0,0 +1,351 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder, http::StatusCode};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::*;
+use sea_orm::sea_query::Expr;
+use uuid::Uuid;
+use crate::config::auth;
+use crate::entity::{oauth_authorization_code, oauth_client, refresh_token, user};
+use crate::dtos::oauth_dto::{OAuthErrorResponse, TokenRequest, TokenResponse};
+use crate::handlers::auth::tokens;
+use crate::handlers::user_handler::AppState;
+use crate::utils::{hash, jwt, pkce, scope};
+
+fn oauth_error(status: StatusCode, error: &str, description: &str) -> HttpResponse {
+    let mut builder = HttpResponse::build(status);
+    if status == StatusCode::UNAUTHORIZED {
+        builder.insert_header(("WWW-Authenticate", "Basic realm=\"oauth\""));
+    }
+    builder
+        .insert_header(("Cache-Control", "no-store"))
+        .json(OAuthErrorResponse {
+            error: error.to_string(),
+            error_description: Some(description.to_string()),
+        })
+}
+
+fn invalid_grant(description: &str) -> HttpResponse {
+    oauth_error(StatusCode::BAD_REQUEST, "invalid_grant", description)
+}
+
+fn server_error(err: DbErr) -> HttpResponse {
+    eprintln!("Database error: {:?}", err);
+    oauth_error(StatusCode::INTERNAL_SERVER_ERROR, "server_error", "Database error")
+}
+
+// POST /oauth/token
+pub async fn token(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    form: web::Form<TokenRequest>,
+) -> impl Responder {
+    let client = match authenticate_client(&data, &req, &form).await {
+        Ok(client) => client,
+        Err(resp) => return resp,
+    };
+
+    // Service account hanya boleh client_credentials, client biasa tidak boleh
+    let unauthorized_client = || {
+        oauth_error(StatusCode::BAD_REQUEST, "unauthorized_client", "This client is not allowed to use this grant_type")
+    };
+
+    match form.grant_type.as_str() {
+        "authorization_code" if client.service_account => unauthorized_client(),
+        "authorization_code" => authorization_code_grant(&data, &client, &form).await,
+        "refresh_token" if client.service_account => unauthorized_client(),
+        "refresh_token" => refresh_token_grant(&data, &client, &form).await,
+        "client_credentials" if !client.service_account => unauthorized_client(),
+        "client_credentials" => client_credentials_grant(&client, &form),
+        _ => oauth_error(StatusCode::BAD_REQUEST, "unsupported_grant_type", "Unsupported grant_type"),
+    }
+}
+
+// Client autentikasi lewat HTTP Basic atau client_id/client_secret di form.
+// Public client (tanpa secret) cukup mengirim client_id, keamanannya dari PKCE.
+async fn authenticate_client(
+    data: &AppState,
+    req: &HttpRequest,
+    form: &TokenRequest,
+) -> Result<oauth_client::Model, HttpResponse> {
+    let basic = req
+        .headers()
+        .get("Authorization")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.strip_prefix("Basic "))
+        .and_then(|encoded| STANDARD.decode(encoded).ok())
+        .and_then(|decoded| String::from_utf8(decoded).ok())
+        .and_then(|pair| pair.split_once(':').map(|(id, secret)| (id.to_string(), secret.to_string())));
+
+    let (client_id, secret) = match basic {
+        Some((id, secret)) => (Some(id), Some(secret)),
+        None => (form.client_id.clone(), form.client_secret.clone()),
+    };
+
+    let invalid_client = || oauth_error(StatusCode::UNAUTHORIZED, "invalid_client", "Client authentication failed");
+
+    let Some(client_id) = client_id else {
+        return Err(invalid_client());
+    };
+
+    let client = match oauth_client::Entity::find_by_id(client_id).one(&data.db).await {
+        Ok(Some(client)) => client,
+        Ok(None) => return Err(invalid_client()),
+        Err(err) => return Err(server_error(err)),
+    };
+
+    if let Some(secret_hash) = &client.secret_hash {
+        let valid = secret
+            .as_deref()
+            .is_some_and(|secret| hash::constant_time_eq(&hash::sha256_hex(secret), secret_hash));
+        if !valid {
+            return Err(invalid_client());
+        }
+    }
+
+    Ok(client)
+}
+
+async fn authorization_code_grant(
+    data: &AppState,
+    client: &oauth_client::Model,
+    form: &TokenRequest,
+) -> HttpResponse {
+    let (Some(code), Some(redirect_uri), Some(verifier)) = (&form.code, &form.redirect_uri, &form.code_verifier) else {
+        return oauth_error(StatusCode::BAD_REQUEST, "invalid_request", "code, redirect_uri and code_verifier are required");
+    };
+
+    let stored = match oauth_authorization_code::Entity::find()
+        .filter(oauth_authorization_code::Column::CodeHash.eq(hash::sha256_hex(code)))
+        .one(&data.db)
+        .await
+    {
+        Ok(Some(stored)) => stored,
+        Ok(None) => return invalid_grant("Invalid authorization code"),
+        Err(err) => return server_error(err),
+    };
+
+    if stored.client_id != client.id || stored.redirect_uri != *redirect_uri || stored.expires_at <= Utc::now() {
+        return invalid_grant("Invalid authorization code");
+    }
+
+    if !pkce::verify_s256(verifier, &stored.code_challenge) {
+        return invalid_grant("PKCE verification failed");
+    }
+
+    // Code hanya boleh ditukar sekali, update bersyarat supaya request paralel tidak lolos dua-duanya
+    match oauth_authorization_code::Entity::update_many()
+        .col_expr(oauth_authorization_code::Column::UsedAt, Expr::value(Utc::now()))
+        .filter(oauth_authorization_code::Column::Id.eq(stored.id))
+        .filter(oauth_authorization_code::Column::UsedAt.is_null())
+        .exec(&data.db)
+        .await
+    {
+        Ok(result) if result.rows_affected == 1 => {}
+        Ok(_) => return invalid_grant("Authorization code has already been used"),
+        Err(err) => return server_error(err),
+    }
+
+    let user_model = match user::Entity::find_by_id(stored.user_id).one(&data.db).await {
+        Ok(Some(user_model)) => user_model,
+        Ok(None) => return invalid_grant("User no longer exists"),
+        Err(err) => return server_error(err),
+    };
+
+    issue(data, client, user_model, scope::parse(&stored.scopes), None).await
+}
+
+async fn refresh_token_grant(
+    data: &AppState,
+    client: &oauth_client::Model,
+    form: &TokenRequest,
+) -> HttpResponse {
+    let Some(token) = &form.refresh_token else {
+        return oauth_error(StatusCode::BAD_REQUEST, "invalid_request", "refresh_token is required");
+    };
+
+    let claims = match jwt::validate_token(token) {
+        Ok(claims) if claims.token_type == "refresh" => claims,
+        _ => return invalid_grant("Invalid refresh token"),
+    };
+
+    match data.denylist.is_revoked(&claims.jti).await {
+        Ok(false) => {}
+        Ok(true) => return invalid_grant("Refresh token has been revoked"),
+        Err(err) => return server_error(err),
+    }
+
+    let stored = match refresh_token::Entity::find()
+        .filter(refresh_token::Column::TokenHash.eq(hash::sha256_hex(token)))
+        .one(&data.db)
+        .await
+    {
+        Ok(Some(stored)) => stored,
+        Ok(None) => return invalid_grant("Invalid refresh token"),
+        Err(err) => return server_error(err),
+    };
+
+    // Refresh token hanya bisa dipakai oleh client yang menerimanya
+    if stored.client_id.as_deref() != Some(client.id.as_str())
+        || claims.user_id() != Some(stored.user_id)
+        || claims.jti != stored.jti
+    {
+        return invalid_grant("Invalid refresh token");
+    }
+
+    if stored.revoked {
+        return invalid_grant("Refresh token has been revoked");
+    }
+
+    match refresh_token::Entity::update_many()
+        .col_expr(refresh_token::Column::UsedAt, Expr::value(Utc::now()))
+        .filter(refresh_token::Column::Id.eq(stored.id))
+        .filter(refresh_token::Column::UsedAt.is_null())
+        .exec(&data.db)
+        .await
+    {
+        Ok(result) if result.rows_affected == 1 => {}
+        Ok(_) => {
+            // Sama seperti /api/auth/refresh: reuse berarti token bocor, matikan seluruh family
+            eprintln!("OAuth refresh token reuse detected for family {}", stored.family_id);
+            if let Err(err) = tokens::revoke_family(data, &stored.family_id).await {
+                eprintln!("Database error: {:?}", err);
+            }
+            return invalid_grant("Refresh token reuse detected");
+        }
+        Err(err) => return server_error(err),
+    }
+
+    // Scope tidak boleh melebihi grant awal maupun scope client saat ini
+    let granted: Vec<String> = scope::parse(stored.scopes.as_deref().unwrap_or_default())
+        .into_iter()
+        .filter(|s| scope::parse(&client.scopes).contains(s))
+        .collect();
+    let scopes = match form.scope.as_deref() {
+        Some(requested) => {
+            let requested = scope::parse(requested);
+            if requested.is_empty() || !scope::is_subset(&requested, &granted) {
+                return oauth_error(StatusCode::BAD_REQUEST, "invalid_scope", "Requested scope exceeds the original grant");
+            }
+            requested
+        }
+        None => granted,
+    };
+    if scopes.is_empty() {
+        return oauth_error(StatusCode::BAD_REQUEST, "invalid_scope", "No scopes remain for this client");
+    }
+
+    match user::Entity::find_by_id(stored.user_id).one(&data.db).await {
+        Ok(Some(user_model)) if user_model.token_version != claims.ver => {
+            invalid_grant("Refresh token has been revoked")
+        }
+        Ok(Some(user_model)) => issue(data, client, user_model, scopes, Some(&stored)).await,
+        Ok(None) => invalid_grant("User no longer exists"),
+        Err(err) => server_error(err),
+    }
+}
+
+// Token untuk service account: sub = "client:<client_id>", tanpa refresh token (RFC 6749 section 4.4.3)
+fn client_credentials_grant(client: &oauth_client::Model, form: &TokenRequest) -> HttpResponse {
+    let allowed = scope::parse(&client.scopes);
+    let scopes = match form.scope.as_deref() {
+        Some(requested) => scope::parse(requested),
+        None => allowed.clone(),
+    };
+    if scopes.is_empty() || !scope::is_subset(&scopes, &allowed) {
+        return oauth_error(StatusCode::BAD_REQUEST, "invalid_scope", "Requested scope is not allowed for this client");
+    }
+
+    let claims = jwt::Claims::new_client_token(
+        &client.id,
+        scopes,
+        Duration::seconds(auth::access_token_ttl_secs()),
+    );
+    let access_token = match jwt::encode_claims(&claims) {
+        Ok(token) => token,
+        Err(err) => {
+            eprintln!("JWT error: {:?}", err);
+            return oauth_error(StatusCode::INTERNAL_SERVER_ERROR, "server_error", "Failed to generate token");
+        }
+    };
+
+    HttpResponse::Ok()
+        .insert_header(("Cache-Control", "no-store"))
+        .json(TokenResponse {
+            access_token,
+            token_type: "Bearer".to_string(),
+            expires_in: claims.lifetime_secs(),
+            refresh_token: None,
+            scope: claims.scopes.as_deref().map(scope::join).unwrap_or_default(),
+        })
+}
+
+// Terbitkan access & refresh token untuk client lewat utils::jwt, refresh token
+// disimpan di refresh_tokens dengan client_id supaya ikut rotasi & reuse detection
+async fn issue(
+    data: &AppState,
+    client: &oauth_client::Model,
+    user_model: user::Model,
+    scopes: Vec<String>,
+    parent: Option<&refresh_token::Model>,
+) -> HttpResponse {
+    let family_id = parent
+        .map(|p| p.family_id.clone())
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+    let mut access_claims = jwt::Claims::new_access_token(
+        user_model.id,
+        user_model.email.clone(),
+        user_model.role.clone(),
+        user_model.token_version,
+        Duration::seconds(auth::access_token_ttl_secs()),
+    );
+    let mut refresh_claims = jwt::Claims::new_refresh_token(
+        user_model.id,
+        user_model.email,
+        user_model.role,
+        user_model.token_version,
+        Duration::seconds(auth::oauth_refresh_token_ttl_secs()),
+    );
+    for claims in [&mut access_claims, &mut refresh_claims] {
+        claims.scopes = Some(scopes.clone());
+        claims.client_id = Some(client.id.clone());
+    }
+
+    let (access, refresh) = match (jwt::encode_claims(&access_claims), jwt::encode_claims(&refresh_claims)) {
+        (Ok(access), Ok(refresh)) => (access, refresh),
+        _ => {
+            eprintln!("JWT generation error");
+            return oauth_error(StatusCode::INTERNAL_SERVER_ERROR, "server_error", "Failed to generate tokens");
+        }
+    };
+
+    let new_token = refresh_token::ActiveModel {
+        id: NotSet,
+        user_id: Set(user_model.id),
+        jti: Set(refresh_claims.jti.clone()),
+        token_hash: Set(hash::sha256_hex(&refresh)),
+        family_id: Set(family_id),
+        parent_id: Set(parent.map(|p| p.id)),
+        expires_at: Set(DateTime::from_timestamp(refresh_claims.exp, 0).unwrap_or_default()),
+        revoked: Set(false),
+        used_at: Set(None),
+        remember_me: Set(false),
+        client_id: Set(Some(client.id.clone())),
+        scopes: Set(Some(scope::join(&scopes))),
+        created_at: NotSet,
+    };
+
+    if let Err(err) = refresh_token::Entity::insert(new_token).exec(&data.db).await {
+        return server_error(err);
+    }
+
+    HttpResponse::Ok()
+        .insert_header(("Cache-Control", "no-store"))
+        .json(TokenResponse {
+            access_token: access,
+            token_type: "Bearer".to_string(),
+            expires_in: access_claims.lifetime_secs(),
+            refresh_token: Some(refresh),
+            scope: scope::join(&scopes),
+        })
+}
@@ -1,18 +1,55 @@
-use actix_web::{web, HttpResponse, Responder};
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
 use sea_orm::*;
+use std::sync::Arc;
+use webauthn_rs::Webauthn;
 use crate::entity::user;
+use crate::config::auth::AuthMode;
+use crate::middleware::auth_middleware::extract;
+use crate::utils::auth_provider::AuthProviderChain;
+use crate::utils::denylist::TokenDenylist;
+use crate::utils::mailer::Mailer;
+use crate::utils::oidc::OidcClient;
+use crate::utils::otp::OtpChannels;
+use crate::utils::secret_box::SecretBox;
+use crate::utils::session_store::SessionStore;
+use crate::utils::scope;
+use crate::utils::token_version::TokenVersionCache;
+use crate::utils::session_revocation::SessionRevocationCache;
 use crate::dtos::user_dto::{CreateUserRequest, UserResponse, ErrorResponse};
 
 // AppState untuk menyimpan database connection
 pub struct AppState {
     pub db: DatabaseConnection,
+    pub denylist: Arc<dyn TokenDenylist>,
+    pub token_versions: TokenVersionCache,
+    // Status revoke user_sessions untuk claim sid
+    pub revoked_sessions: SessionRevocationCache,
+    pub auth_mode: AuthMode,
+    pub sessions: Arc<dyn SessionStore>,
+    // None kalau login OIDC tidak dikonfigurasi
+    pub oidc: Option<Arc<OidcClient>>,
+    // Provider login password (AUTH_PROVIDERS)
+    pub auth_providers: AuthProviderChain,
+    // Transport email (MAIL_TRANSPORT)
+    pub mailer: Arc<dyn Mailer>,
+    // Channel pengiriman kode login OTP (email, SMS)
+    pub otp_channels: OtpChannels,
+    // Enkripsi secret MFA, None kalau MFA_ENCRYPTION_KEY tidak diisi
+    pub secret_box: Option<SecretBox>,
+    // Relying party WebAuthn (WEBAUTHN_RP_*)
+    pub webauthn: Webauthn,
 }
 
 // Handler Create User
 pub async fn create_user(
     data: web::Data<AppState>,
+    req: HttpRequest,
     req_body: web::Json<CreateUserRequest>,
 ) -> impl Responder {
+    if let Err(resp) = extract::require_scope(&req, scope::USERS_WRITE) {
+        return resp;
+    }
+
     // Validasi input
     if req_body.name.trim().is_empty() {
         return HttpResponse::BadRequest().json(ErrorResponse {
@@ -31,8 +68,11 @@ pub async fn create_user(
         id: NotSet,
         username: Set(req_body.name.trim().to_string()),
         email: Set(req_body.email.trim().to_lowercase()),
+        phone: NotSet,
+        phone_verified_at: NotSet,
         password_hash: Set(String::new()), // Empty for now, auth endpoints will handle this
         role: Set("user".to_string()),
+        token_version: NotSet,
         created_at: NotSet,
         updated_at: NotSet,
     };
@@ -5,24 +5,98 @@ mod handlers;
 mod routes;
 mod utils;
 mod middleware;
+mod cli;
 
 use actix_web::{web, App, HttpServer};
 use dotenvy::dotenv;
 use std::env;
+use std::sync::Arc;
+use std::time::Duration;
 use handlers::user_handler::AppState;
+use utils::{denylist, session_store};
+use config::auth::AuthMode;
+use utils::token_version::TokenVersionCache;
+use utils::session_revocation::SessionRevocationCache;
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     // 1. Load Environment Variables (.env)
     dotenv().ok();
     
+    // Load JWT key ring lebih awal supaya konfigurasi yang salah langsung ketahuan
+    utils::keys::init();
+
+    // Perintah CLI (mis. `keys promote <kid>`) dijalankan tanpa start server
+    let args: Vec<String> = env::args().skip(1).collect();
+    if !args.is_empty() {
+        std::process::exit(cli::run(&args));
+    }
+
     // 2. Setup Database
     // Pastikan function establish_connection sudah ada di src/config/db.rs
     let db = config::db::init_db().await;
     
     // 3. Simpan DB ke dalam State
     // Ini biar database bisa diakses dari semua handler/routes
-    let state = web::Data::new(AppState { db });
+    let denylist = denylist::from_env(&db);
+    let sessions = session_store::from_env(&db);
+    let token_version_ttl = env::var("TOKEN_VERSION_CACHE_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(30);
+    let auth_providers = utils::auth_provider::AuthProviderChain::from_env(&db);
+    let mailer = utils::mailer::from_env();
+    let state = web::Data::new(AppState {
+        db,
+        denylist: denylist.clone(),
+        token_versions: TokenVersionCache::new(Duration::from_secs(token_version_ttl)),
+        revoked_sessions: SessionRevocationCache::new(Duration::from_secs(token_version_ttl)),
+        auth_mode: AuthMode::from_env(),
+        sessions: sessions.clone(),
+        oidc: utils::oidc::OidcClient::from_env().map(Arc::new),
+        auth_providers,
+        mailer: mailer.clone(),
+        otp_channels: utils::otp::OtpChannels::from_env(mailer),
+        secret_box: utils::secret_box::SecretBox::from_env(),
+        webauthn: utils::webauthn::from_env(),
+    });
+
+    // Bersihkan jti denylist & server session yang sudah expired secara berkala
+    let prune_secs = env::var("DENYLIST_PRUNE_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(600);
+    actix_web::rt::spawn(async move {
+        let mut interval = actix_web::rt::time::interval(Duration::from_secs(prune_secs));
+        loop {
+            interval.tick().await;
+            match denylist.prune_expired().await {
+                Ok(0) => {}
+                Ok(n) => println!("Pruned {} expired denylist entries", n),
+                Err(e) => eprintln!("Failed to prune denylist: {:?}", e),
+            }
+            match sessions.prune_expired().await {
+                Ok(0) => {}
+                Ok(n) => println!("Pruned {} expired server sessions", n),
+                Err(e) => eprintln!("Failed to prune server sessions: {:?}", e),
+            }
+        }
+    });
+
+    // Reload key ring dari file supaya promote/retire di instance lain ikut terbaca
+    let reload_secs = env::var("JWT_KEYRING_RELOAD_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(60);
+    actix_web::rt::spawn(async move {
+        let mut interval = actix_web::rt::time::interval(Duration::from_secs(reload_secs));
+        loop {
+            interval.tick().await;
+            if let Err(e) = utils::keys::reload() {
+                eprintln!("Failed to reload JWT key ring: {}", e);
+            }
+        }
+    });
 
     // 4. Ambil Host & Port dari .env
     let host = env::var("SERVER_HOST").unwrap_or("127.0.0.1".to_string());
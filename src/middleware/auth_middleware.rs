@@ -1,17 +1,24 @@
 use actix_web::{
     dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
-    Error, HttpMessage, HttpResponse, body::EitherBody,
+    web, Error, HttpMessage, HttpRequest, HttpResponse, body::EitherBody,
 };
 use futures_util::future::LocalBoxFuture;
 use std::future::{ready, Ready};
-use crate::utils::jwt;
+use std::rc::Rc;
+use crate::config::cookie::{self, ACCESS_TOKEN, SESSION_ID};
+use sea_orm::EntityTrait;
+use crate::entity::oauth_client;
+use crate::utils::{api_key, hash, jwt, personal_token};
+use crate::utils::jwt::Principal;
 use crate::dtos::common_dto::ApiResponse;
+use crate::handlers::user_handler::AppState;
+use super::csrf;
 
 pub struct JwtMiddleware;
 
 impl<S, B> Transform<S, ServiceRequest> for JwtMiddleware
 where
-    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
     S::Future: 'static,
     B: 'static,
 {
@@ -22,17 +29,78 @@ where
     type Future = Ready<Result<Self::Transform, Self::InitError>>;
 
     fn new_transform(&self, service: S) -> Self::Future {
-        ready(Ok(JwtMiddlewareService { service }))
+        ready(Ok(JwtMiddlewareService { service: Rc::new(service) }))
     }
 }
 
+pub const API_KEY_HEADER: &str = "X-API-Key";
+
 pub struct JwtMiddlewareService<S> {
-    service: S,
+    service: Rc<S>,
+}
+
+// Kredensial yang dibawa request, urutan prioritas sesuai urutan varian
+pub enum Credential {
+    // Authorization: Bearer <jwt>
+    Bearer(String),
+    // X-API-Key: <api key>, untuk client machine-to-machine
+    ApiKey(String),
+    // Cookie session_id (AUTH_MODE=session)
+    SessionCookie(String),
+    // Cookie access_token (AUTH_MODE=jwt)
+    AccessCookie(String),
+}
+
+pub fn extract_credential(req: &HttpRequest) -> Option<Credential> {
+    if let Some(token) = req
+        .headers()
+        .get("Authorization")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.strip_prefix("Bearer "))
+    {
+        return Some(Credential::Bearer(token.to_string()));
+    }
+
+    if let Some(key) = req.headers().get(API_KEY_HEADER).and_then(|h| h.to_str().ok()) {
+        return Some(Credential::ApiKey(key.to_string()));
+    }
+
+    // Fallback: cek cookie jika header tidak ada
+    if let Some(session_id) = cookie::get(req, SESSION_ID) {
+        return Some(Credential::SessionCookie(session_id));
+    }
+
+    cookie::get(req, ACCESS_TOKEN).map(Credential::AccessCookie)
+}
+
+// Extract JWT dari Authorization header atau cookie
+pub fn extract_access_token(req: &HttpRequest) -> Option<String> {
+    match extract_credential(req)? {
+        Credential::Bearer(token) | Credential::AccessCookie(token) => Some(token),
+        Credential::SessionCookie(_) => cookie::get(req, ACCESS_TOKEN),
+        Credential::ApiKey(_) => None,
+    }
+}
+
+fn unauthorized<B>(req: ServiceRequest, message: &str) -> ServiceResponse<EitherBody<B>> {
+    let (request, _pl) = req.into_parts();
+    let response = HttpResponse::Unauthorized()
+        .json(ApiResponse::<()>::error(message))
+        .map_into_right_body();
+    ServiceResponse::new(request, response)
+}
+
+fn internal_error<B>(req: ServiceRequest) -> ServiceResponse<EitherBody<B>> {
+    let (request, _pl) = req.into_parts();
+    let response = HttpResponse::InternalServerError()
+        .json(ApiResponse::<()>::error("Database error"))
+        .map_into_right_body();
+    ServiceResponse::new(request, response)
 }
 
 impl<S, B> Service<ServiceRequest> for JwtMiddlewareService<S>
 where
-    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
     S::Future: 'static,
     B: 'static,
 {
@@ -43,66 +111,142 @@ where
     forward_ready!(service);
 
     fn call(&self, req: ServiceRequest) -> Self::Future {
-        // Extract token from Authorization header or cookie
-        let token = req
-            .headers()
-            .get("Authorization")
-            .and_then(|h| h.to_str().ok())
-            .and_then(|h| {
-                if h.starts_with("Bearer ") {
-                    Some(h[7..].to_string())
-                } else {
-                    None
+        let service = Rc::clone(&self.service);
+
+        Box::pin(async move {
+            let Some(state) = req.app_data::<web::Data<AppState>>().cloned() else {
+                return Ok(internal_error(req));
+            };
+
+            let credential = extract_credential(req.request());
+
+            // Auth dari cookie dikirim otomatis oleh browser, jadi request unsafe wajib
+            // membawa CSRF token. Bearer header tidak terpengaruh.
+            let from_cookie = matches!(
+                credential,
+                Some(Credential::SessionCookie(_)) | Some(Credential::AccessCookie(_))
+            );
+            if from_cookie && !csrf::verify(req.request()) {
+                let (request, _pl) = req.into_parts();
+                let response = HttpResponse::Forbidden()
+                    .json(ApiResponse::<()>::error("Invalid or missing CSRF token"))
+                    .map_into_right_body();
+                return Ok(ServiceResponse::new(request, response));
+            }
+
+            let claims = match credential {
+                None => return Ok(unauthorized(req, "Missing authorization token")),
+                Some(Credential::SessionCookie(session_id)) => {
+                    // Session server-side, dicari berdasarkan hash dari id di cookie
+                    match state.sessions.get(&hash::sha256_hex(&session_id)).await {
+                        Ok(Some(session)) => session.to_claims(),
+                        Ok(None) => return Ok(unauthorized(req, "Invalid or expired session")),
+                        Err(err) => {
+                            eprintln!("Session lookup error: {:?}", err);
+                            return Ok(internal_error(req));
+                        }
+                    }
                 }
-            })
-            .or_else(|| {
-                // Fallback: cek cookie jika header tidak ada
-                req.cookie("access_token")
-                    .map(|c| c.value().to_string())
-            });
-
-        match token {
-            Some(token) => {
-                // Validate token
-                match jwt::validate_token(&token) {
-                    Ok(claims) => {
-                        // Insert claims into request extensions for access in handlers
-                        req.extensions_mut().insert(claims);
-                        
-                        let fut = self.service.call(req);
-                        Box::pin(async move {
-                            let res = fut.await?;
-                            Ok(res.map_into_left_body())
-                        })
+                Some(Credential::ApiKey(key)) => {
+                    match api_key::authenticate(&state.db, &key).await {
+                        Ok(Some((key_model, owner))) => api_key::to_claims(&key_model, &owner),
+                        Ok(None) => return Ok(unauthorized(req, "Invalid or expired API key")),
+                        Err(err) => {
+                            eprintln!("API key lookup error: {:?}", err);
+                            return Ok(internal_error(req));
+                        }
                     }
-                    Err(_) => {
-                        let (request, _pl) = req.into_parts();
-                        let response = HttpResponse::Unauthorized()
-                            .json(ApiResponse::<()>::error("Invalid or expired token"))
-                            .map_into_right_body();
-                        Box::pin(async move {
-                            Ok(ServiceResponse::new(request, response))
-                        })
+                }
+                Some(Credential::Bearer(token)) if token.starts_with(personal_token::TOKEN_PREFIX) => {
+                    match personal_token::authenticate(&state.db, &token).await {
+                        Ok(Some((token_model, owner))) => personal_token::to_claims(&token_model, &owner),
+                        Ok(None) => return Ok(unauthorized(req, "Invalid or expired access token")),
+                        Err(err) => {
+                            eprintln!("Personal access token lookup error: {:?}", err);
+                            return Ok(internal_error(req));
+                        }
+                    }
+                }
+                Some(Credential::Bearer(token)) | Some(Credential::AccessCookie(token)) => {
+                    // Validate token
+                    let claims = match jwt::validate_token(&token) {
+                        Ok(claims) => claims,
+                        Err(err) => return Ok(unauthorized(req, jwt::rejection_message(&err))),
+                    };
+
+                    // Hanya access token yang boleh dipakai untuk akses endpoint
+                    if claims.token_type != "access" {
+                        return Ok(unauthorized(req, "Invalid token type"));
+                    }
+
+                    // Tolak token yang sudah di-revoke lewat logout
+                    match state.denylist.is_revoked(&claims.jti).await {
+                        Ok(false) => {}
+                        Ok(true) => return Ok(unauthorized(req, "Token has been revoked")),
+                        Err(err) => {
+                            eprintln!("Denylist lookup error: {:?}", err);
+                            return Ok(internal_error(req));
+                        }
+                    }
+
+                    claims
+                }
+            };
+
+            match claims.principal() {
+                // Tolak token/session yang terbit sebelum "logout everywhere"
+                Some(Principal::User(user_id)) => {
+                    match state.token_versions.current(&state.db, user_id).await {
+                        Ok(Some(version)) if version == claims.ver => {}
+                        Ok(Some(_)) => return Ok(unauthorized(req, "Token has been revoked")),
+                        Ok(None) => return Ok(unauthorized(req, "User no longer exists")),
+                        Err(err) => {
+                            eprintln!("Token version lookup error: {:?}", err);
+                            return Ok(internal_error(req));
+                        }
+                    }
+                }
+                // Token service account mati begitu client-nya dihapus
+                Some(Principal::Client(client_id)) => {
+                    match oauth_client::Entity::find_by_id(client_id).one(&state.db).await {
+                        Ok(Some(client)) if client.service_account => {}
+                        Ok(_) => return Ok(unauthorized(req, "Client no longer exists")),
+                        Err(err) => {
+                            eprintln!("Client lookup error: {:?}", err);
+                            return Ok(internal_error(req));
+                        }
                     }
                 }
+                None => return Ok(unauthorized(req, "Invalid token subject")),
             }
-            None => {
-                let (request, _pl) = req.into_parts();
-                let response = HttpResponse::Unauthorized()
-                    .json(ApiResponse::<()>::error("Missing authorization token"))
-                    .map_into_right_body();
-                Box::pin(async move {
-                    Ok(ServiceResponse::new(request, response))
-                })
+
+            // Semua token dalam session yang sudah di-revoke ikut ditolak, termasuk
+            // access token dari rotasi sebelumnya yang tidak masuk denylist
+            if let Some(sid) = &claims.sid {
+                match state.revoked_sessions.is_revoked(&state.db, sid).await {
+                    Ok(false) => {}
+                    Ok(true) => return Ok(unauthorized(req, "Session has been revoked")),
+                    Err(err) => {
+                        eprintln!("Session lookup error: {:?}", err);
+                        return Ok(internal_error(req));
+                    }
+                }
             }
-        }
+
+            // Insert claims into request extensions for access in handlers
+            req.extensions_mut().insert(claims);
+
+            let res = service.call(req).await?;
+            Ok(res.map_into_left_body())
+        })
     }
 }
 
 // Helper untuk extract claims dari request
 pub mod extract {
     use actix_web::{HttpRequest, HttpResponse, HttpMessage};
-    use crate::utils::jwt::Claims;
+    use crate::utils::jwt::{Actor, Claims, Principal};
+    use crate::utils::scope;
     use crate::dtos::common_dto::ApiResponse;
 
     #[allow(dead_code)]
@@ -120,11 +264,76 @@ pub mod extract {
     pub fn require_admin(req: &HttpRequest) -> Result<Claims, HttpResponse> {
         let claims = get_claims(req)?;
         
-        if claims.role != "admin" {
+        // Token terbatas milik admin tetap butuh scope "admin"
+        if claims.role != "admin" || !claims.has_scope(scope::ADMIN) {
             return Err(HttpResponse::Forbidden()
                 .json(ApiResponse::<()>::error("Admin access required")));
         }
         
         Ok(claims)
     }
+
+    // Tolak request yang tokennya tidak punya scope ini
+    #[allow(dead_code)]
+    pub fn require_scope(req: &HttpRequest, required: &str) -> Result<Claims, HttpResponse> {
+        let claims = get_claims(req)?;
+
+        if !claims.has_scope(required) {
+            return Err(HttpResponse::Forbidden()
+                .json(ApiResponse::<()>::error(&format!("Missing required scope: {}", required))));
+        }
+
+        Ok(claims)
+    }
+
+    // Admin yang sedang impersonate user ini, None untuk request biasa
+    #[allow(dead_code)]
+    pub fn get_impersonator(req: &HttpRequest) -> Option<Actor> {
+        req.extensions()
+            .get::<Claims>()
+            .and_then(|claims| claims.act.clone())
+    }
+
+    // User atau service account pemilik request
+    #[allow(dead_code)]
+    pub fn get_principal(req: &HttpRequest) -> Result<Principal, HttpResponse> {
+        get_claims(req)?.principal().ok_or_else(|| {
+            HttpResponse::Unauthorized()
+                .json(ApiResponse::<()>::error("Unauthorized"))
+        })
+    }
+
+    // Claims milik user nyata (bukan service account)
+    fn user_claims(req: &HttpRequest) -> Result<(Claims, i32), HttpResponse> {
+        let claims = get_claims(req)?;
+        match claims.user_id() {
+            Some(user_id) => Ok((claims, user_id)),
+            None => Err(HttpResponse::Forbidden()
+                .json(ApiResponse::<()>::error("This endpoint requires a user account"))),
+        }
+    }
+
+    // Untuk endpoint pengelolaan akun user sendiri (sesi, token, faktor kedua).
+    // Token terbatas harus punya scope "account"
+    #[allow(dead_code)]
+    pub fn require_user(req: &HttpRequest) -> Result<(Claims, i32), HttpResponse> {
+        let (claims, user_id) = user_claims(req)?;
+        if !claims.has_scope(scope::ACCOUNT) {
+            return Err(HttpResponse::Forbidden()
+                .json(ApiResponse::<()>::error(&format!("Missing required scope: {}", scope::ACCOUNT))));
+        }
+        Ok((claims, user_id))
+    }
+
+    // Untuk pengaturan keamanan akun: hanya dari login interaktif user sendiri,
+    // bukan API key, token terbatas (PAT / OAuth) atau sesi impersonation
+    #[allow(dead_code)]
+    pub fn require_interactive_user(req: &HttpRequest) -> Result<(Claims, i32), HttpResponse> {
+        let (claims, user_id) = user_claims(req)?;
+        if claims.token_type != "access" || claims.scopes.is_some() || claims.is_impersonated() {
+            return Err(HttpResponse::Forbidden()
+                .json(ApiResponse::<()>::error("This action requires an interactive login")));
+        }
+        Ok((claims, user_id))
+    }
 }
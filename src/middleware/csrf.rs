@@ -0,0 +1,58 @@
+use actix_web::{HttpRequest, cookie::{Cookie, time::Duration as CookieDuration}, http::Method};
+use std::env;
+use crate::config::cookie::{self, CSRF_TOKEN};
+use crate::utils::hash;
+
+// Proteksi CSRF pola double-submit: saat login server mengirim cookie csrf_token
+// (bisa dibaca JavaScript), lalu setiap request unsafe yang auth-nya dari cookie
+// wajib mengirim nilai yang sama di header X-CSRF-Token. Situs lain bisa membuat
+// browser mengirim cookie, tapi tidak bisa membaca nilainya untuk diisi ke header.
+//
+// CSRF_EXEMPT_PATHS: daftar prefix path yang dikecualikan, dipisah koma
+// (mis. "/api/webhooks,/api/auth/introspect").
+
+pub const HEADER_NAME: &str = "X-CSRF-Token";
+
+// Form HTML biasa tidak bisa mengirim header, jadi path ini membawa CSRF token
+// di field form `csrf_token` dan handler-nya wajib memanggil verify_form.
+const FORM_TOKEN_PATHS: &[&str] = &["/oauth/authorize"];
+
+pub fn new_cookie(max_age: Option<CookieDuration>) -> Cookie<'static> {
+    cookie::policy().build(CSRF_TOKEN, hash::random_token(32), max_age)
+}
+
+// true kalau request boleh lanjut. Hanya dipanggil untuk request yang auth-nya dari cookie.
+pub fn verify(req: &HttpRequest) -> bool {
+    if is_safe_method(req.method()) || is_exempt(req.path()) || FORM_TOKEN_PATHS.contains(&req.path()) {
+        return true;
+    }
+
+    let cookie = cookie::get(req, CSRF_TOKEN);
+    let header = req.headers().get(HEADER_NAME).and_then(|h| h.to_str().ok());
+
+    match (cookie, header) {
+        (Some(cookie), Some(header)) => hash::constant_time_eq(&cookie, header),
+        _ => false,
+    }
+}
+
+// Versi form dari verify: nilai dari field form dicocokkan dengan cookie
+pub fn verify_form(req: &HttpRequest, form_value: Option<&str>) -> bool {
+    match (cookie::get(req, CSRF_TOKEN), form_value) {
+        (Some(cookie), Some(value)) => hash::constant_time_eq(&cookie, value),
+        _ => false,
+    }
+}
+
+fn is_safe_method(method: &Method) -> bool {
+    matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS | Method::TRACE)
+}
+
+fn is_exempt(path: &str) -> bool {
+    env::var("CSRF_EXEMPT_PATHS")
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|prefix| !prefix.is_empty())
+        .any(|prefix| path.starts_with(prefix))
+}
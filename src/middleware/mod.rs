@@ -1 +1,2 @@
 pub mod auth_middleware;
+pub mod csrf;
@@ -0,0 +1,22 @@
+use actix_web::web;
+use crate::handlers::admin;
+use crate::middleware::auth_middleware::JwtMiddleware;
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/admin")
+            // Semua endpoint admin butuh JWT, role dicek di handler lewat require_admin
+            .wrap(JwtMiddleware)
+            .route("/keys", web::get().to(admin::list_keys))
+            .route("/keys/{kid}/promote", web::post().to(admin::promote_key))
+            .route("/keys/{kid}/retire", web::post().to(admin::retire_key))
+            .route("/users/{id}/logout-all", web::post().to(admin::logout_all_user))
+            .route("/users/{id}/api-keys", web::get().to(admin::list_user_api_keys))
+            .route("/oauth/clients", web::get().to(admin::list_oauth_clients))
+            .route("/oauth/clients", web::post().to(admin::create_oauth_client))
+            .route("/oauth/clients/{client_id}", web::delete().to(admin::delete_oauth_client))
+            // "/impersonate/stop" harus didaftarkan sebelum "/impersonate/{user_id}"
+            .route("/impersonate/stop", web::post().to(admin::stop_impersonation))
+            .route("/impersonate/{user_id}", web::post().to(admin::start_impersonation))
+    );
+}
@@ -1,5 +1,6 @@
 use actix_web::web;
 use crate::handlers::auth;
+use crate::middleware::auth_middleware::JwtMiddleware;
 
 pub fn config(cfg: &mut web::ServiceConfig) {
     cfg.service(
@@ -7,5 +8,69 @@ pub fn config(cfg: &mut web::ServiceConfig) {
             .route("/register", web::post().to(auth::register))
             .route("/login", web::post().to(auth::login))
             .route("/logout", web::post().to(auth::logout))
+            .route("/refresh", web::post().to(auth::refresh))
+            .route("/introspect", web::post().to(auth::introspect))
+            .route("/oidc/login", web::get().to(auth::oidc_login))
+            .route("/oidc/callback", web::get().to(auth::oidc_callback))
+            .route("/magic-link", web::post().to(auth::request_magic_link))
+            .route("/magic-link/verify", web::post().to(auth::verify_magic_link))
+            .route("/otp", web::post().to(auth::request_login_code))
+            .route("/otp/verify", web::post().to(auth::verify_login_code))
+            .route("/mfa/verify", web::post().to(auth::verify_mfa))
+            .route("/webauthn/mfa/start", web::post().to(auth::start_mfa))
+            .route("/webauthn/mfa/finish", web::post().to(auth::finish_mfa))
+            .route("/webauthn/login/start", web::post().to(auth::start_passkey_login))
+            .route("/webauthn/login/finish", web::post().to(auth::finish_passkey_login))
+            // Protected endpoint - requires JWT
+            .service(
+                web::resource("/logout-all")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(auth::logout_all))
+            )
+            .service(
+                web::scope("/phone")
+                    .wrap(JwtMiddleware)
+                    .route("/verify/start", web::post().to(auth::start_phone_verification))
+                    .route("/verify", web::post().to(auth::confirm_phone_verification))
+            )
+            .service(
+                web::scope("/mfa")
+                    .wrap(JwtMiddleware)
+                    .route("", web::get().to(auth::mfa_status))
+                    .route("/totp/setup", web::post().to(auth::setup_totp))
+                    .route("/totp/confirm", web::post().to(auth::confirm_totp))
+                    .route("/totp/disable", web::post().to(auth::disable_totp))
+                    .route("/recovery-codes", web::post().to(auth::regenerate_recovery_codes))
+            )
+            .service(
+                web::scope("/webauthn")
+                    .wrap(JwtMiddleware)
+                    .route("/register/start", web::post().to(auth::start_registration))
+                    .route("/register/finish", web::post().to(auth::finish_registration))
+                    .route("/credentials", web::get().to(auth::list_credentials))
+                    .route("/credentials/{id}", web::delete().to(auth::delete_credential))
+                    .route("/reauth/start", web::post().to(auth::start_reauthentication))
+            )
+            .service(
+                web::scope("/sessions")
+                    .wrap(JwtMiddleware)
+                    .route("", web::get().to(auth::list_sessions))
+                    .route("/{id}", web::delete().to(auth::revoke_session))
+            )
+            .service(
+                web::scope("/tokens")
+                    .wrap(JwtMiddleware)
+                    .route("", web::get().to(auth::list_personal_tokens))
+                    .route("", web::post().to(auth::create_personal_token))
+                    .route("/{id}", web::patch().to(auth::rename_personal_token))
+                    .route("/{id}", web::delete().to(auth::revoke_personal_token))
+            )
+            .service(
+                web::scope("/api-keys")
+                    .wrap(JwtMiddleware)
+                    .route("", web::get().to(auth::list_api_keys))
+                    .route("", web::post().to(auth::create_api_key))
+                    .route("/{id}", web::delete().to(auth::revoke_api_key))
+            )
     );
 }
@@ -2,11 +2,17 @@ use actix_web::web;
 
 pub mod user;
 pub mod auth;
+pub mod admin;
+pub mod well_known;
+pub mod oauth;
 
 pub fn config(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/api")
             .configure(user::config)
             .configure(auth::config)
-    );
+            .configure(admin::config)
+    )
+    .configure(well_known::config)
+    .configure(oauth::config);
 }
@@ -0,0 +1,18 @@
+use actix_web::web;
+use crate::handlers::oauth;
+use crate::middleware::auth_middleware::JwtMiddleware;
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/oauth")
+            // User harus sudah login (cookie dari /api/auth/login) untuk memberi consent
+            .service(
+                web::resource("/authorize")
+                    .wrap(JwtMiddleware)
+                    .route(web::get().to(oauth::authorize))
+                    .route(web::post().to(oauth::authorize_decision))
+            )
+            // Client autentikasi sendiri di handler
+            .route("/token", web::post().to(oauth::token))
+    );
+}
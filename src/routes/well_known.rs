@@ -0,0 +1,9 @@
+use actix_web::web;
+use crate::handlers::jwks_handler;
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/.well-known")
+            .route("/jwks.json", web::get().to(jwks_handler::jwks))
+    );
+}
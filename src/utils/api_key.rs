@@ -0,0 +1,78 @@
+use chrono::{Duration, Utc};
+use sea_orm::*;
+use crate::config::auth;
+use crate::entity::{api_key, user};
+use super::{hash, jwt::Claims};
+
+// Semua API key diawali ini supaya mudah dikenali (mis. oleh secret scanner)
+pub const KEY_PREFIX: &str = "ak_";
+
+// Panjang awalan yang disimpan apa adanya dan boleh ditampilkan ke user
+const VISIBLE_PREFIX_LEN: usize = KEY_PREFIX.len() + 8;
+
+// last_used_at tidak di-update di setiap request, cukup sekali per menit
+const LAST_USED_RESOLUTION_SECS: i64 = 60;
+
+// Generate API key baru, return (key lengkap, awalan yang bisa ditampilkan)
+pub fn generate() -> (String, String) {
+    let key = format!("{}{}", KEY_PREFIX, hash::random_token(32));
+    let prefix = key[..VISIBLE_PREFIX_LEN].to_string();
+    (key, prefix)
+}
+
+// Cari API key yang masih aktif beserta pemiliknya
+pub async fn authenticate(
+    db: &DatabaseConnection,
+    key: &str,
+) -> Result<Option<(api_key::Model, user::Model)>, DbErr> {
+    if !key.starts_with(KEY_PREFIX) {
+        return Ok(None);
+    }
+
+    let now = Utc::now();
+    let found = api_key::Entity::find()
+        .filter(api_key::Column::KeyHash.eq(hash::sha256_hex(key)))
+        .filter(api_key::Column::RevokedAt.is_null())
+        .filter(
+            Condition::any()
+                .add(api_key::Column::ExpiresAt.is_null())
+                .add(api_key::Column::ExpiresAt.gt(now)),
+        )
+        .find_also_related(user::Entity)
+        .one(db)
+        .await?;
+
+    let Some((key_model, Some(user_model))) = found else {
+        return Ok(None);
+    };
+
+    let stale = key_model
+        .last_used_at
+        .is_none_or(|at| now - at > Duration::seconds(LAST_USED_RESOLUTION_SECS));
+    if stale {
+        api_key::Entity::update_many()
+            .col_expr(api_key::Column::LastUsedAt, sea_query::Expr::value(now))
+            .filter(api_key::Column::Id.eq(key_model.id))
+            .exec(db)
+            .await?;
+    }
+
+    Ok(Some((key_model, user_model)))
+}
+
+// Identitas yang dimasukkan ke request extensions, setara dengan access token pemilik key
+pub fn to_claims(key: &api_key::Model, owner: &user::Model) -> Claims {
+    let mut claims = Claims::new_access_token(
+        owner.id,
+        owner.email.clone(),
+        owner.role.clone(),
+        owner.token_version,
+        Duration::seconds(auth::access_token_ttl_secs()),
+    );
+    if let Some(expires_at) = key.expires_at {
+        claims.exp = claims.exp.min(expires_at.timestamp());
+    }
+    claims.jti = format!("api_key:{}", key.id);
+    claims.token_type = "api_key".to_string();
+    claims
+}
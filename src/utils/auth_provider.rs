@@ -0,0 +1,261 @@
+use async_trait::async_trait;
+use ldap3::{drive, ldap_escape, LdapConnAsync, LdapConnSettings, Scope, SearchEntry};
+use sea_orm::*;
+use std::env;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+use crate::entity::user;
+use super::hash;
+use super::identity::{self, ExternalIdentity};
+
+// Sumber verifikasi username/password untuk POST /api/auth/login.
+// AUTH_PROVIDERS berisi daftar provider dipisah koma, dicoba berurutan
+// (default "database"), mis. "ldap,database" untuk staf di direktori
+// perusahaan dengan fallback akun lokal.
+
+// LDAP result code untuk password/DN salah
+const LDAP_INVALID_CREDENTIALS: u32 = 49;
+
+#[derive(Debug)]
+pub enum AuthProviderError {
+    Database(DbErr),
+    Password(bcrypt::BcryptError),
+    Ldap(ldap3::LdapError),
+    Config(String),
+}
+
+impl fmt::Display for AuthProviderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuthProviderError::Database(err) => write!(f, "Database error: {:?}", err),
+            AuthProviderError::Password(err) => write!(f, "Password verification error: {:?}", err),
+            AuthProviderError::Ldap(err) => write!(f, "LDAP error: {}", err),
+            AuthProviderError::Config(msg) => write!(f, "Auth provider misconfigured: {}", msg),
+        }
+    }
+}
+
+impl From<DbErr> for AuthProviderError {
+    fn from(err: DbErr) -> Self {
+        AuthProviderError::Database(err)
+    }
+}
+
+impl From<ldap3::LdapError> for AuthProviderError {
+    fn from(err: ldap3::LdapError) -> Self {
+        AuthProviderError::Ldap(err)
+    }
+}
+
+// Hasil autentikasi yang berhasil, sebelum dipetakan ke user lokal
+pub enum AuthenticatedIdentity {
+    // Provider sudah memegang user lokal (provider database)
+    Local(user::Model),
+    // Identitas dari sistem luar, ditautkan lewat user_identities
+    External(ExternalIdentity),
+}
+
+#[async_trait]
+pub trait AuthProvider: Send + Sync {
+    fn name(&self) -> &'static str;
+    // Ok(None) = kredensial tidak cocok di provider ini, lanjut ke provider berikutnya
+    async fn authenticate(&self, login: &str, password: &str) -> Result<Option<AuthenticatedIdentity>, AuthProviderError>;
+}
+
+// Provider bawaan: users.email + bcrypt users.password_hash
+pub struct DbAuthProvider {
+    db: DatabaseConnection,
+}
+
+impl DbAuthProvider {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl AuthProvider for DbAuthProvider {
+    fn name(&self) -> &'static str {
+        "database"
+    }
+
+    async fn authenticate(&self, login: &str, password: &str) -> Result<Option<AuthenticatedIdentity>, AuthProviderError> {
+        let found = user::Entity::find()
+            .filter(user::Column::Email.eq(login.trim().to_lowercase()))
+            .one(&self.db)
+            .await?;
+
+        // User dari IdP/LDAP tidak punya password lokal
+        let Some(user_model) = found.filter(|u| !u.password_hash.is_empty()) else {
+            return Ok(None);
+        };
+
+        match hash::verify_password(password, &user_model.password_hash) {
+            Ok(true) => Ok(Some(AuthenticatedIdentity::Local(user_model))),
+            Ok(false) => Ok(None),
+            Err(err) => Err(AuthProviderError::Password(err)),
+        }
+    }
+}
+
+// Provider LDAP: cari entry user (search), lalu bind dengan DN entry + password user.
+//   LDAP_URL              mis. ldap://localhost:389 atau ldaps://ldap.example.com
+//                         (ldap:// tanpa TLS diperbolehkan supaya bisa dites dengan server LDAP lokal)
+//   LDAP_STARTTLS         "true" untuk upgrade koneksi ldap:// ke TLS
+//   LDAP_BIND_DN          DN service account untuk search (kosong = anonymous)
+//   LDAP_BIND_PASSWORD
+//   LDAP_BASE_DN          mis. ou=people,dc=example,dc=com
+//   LDAP_USER_FILTER      default (|(mail={login})(uid={login})), {login} di-escape
+//   LDAP_EMAIL_ATTR       default mail
+//   LDAP_NAME_ATTR        default cn
+//   LDAP_CREATE_USERS     "true" = buat user lokal untuk entry yang belum punya akun (default: tidak).
+//                         Atribut email dari direktori hanya dipakai untuk menautkan akun tanpa
+//                         password lokal dan bukan admin, lihat identity::find_or_link_user
+pub struct LdapAuthProvider {
+    url: String,
+    starttls: bool,
+    bind_dn: Option<String>,
+    bind_password: String,
+    base_dn: String,
+    user_filter: String,
+    email_attr: String,
+    name_attr: String,
+}
+
+impl LdapAuthProvider {
+    pub fn from_env() -> Self {
+        Self {
+            url: env::var("LDAP_URL").unwrap_or("ldap://localhost:389".to_string()),
+            starttls: env::var("LDAP_STARTTLS").map(|v| v == "true").unwrap_or(false),
+            bind_dn: env::var("LDAP_BIND_DN").ok().filter(|v| !v.is_empty()),
+            bind_password: env::var("LDAP_BIND_PASSWORD").unwrap_or_default(),
+            base_dn: env::var("LDAP_BASE_DN").expect("LDAP_BASE_DN must be set when the ldap provider is enabled"),
+            user_filter: env::var("LDAP_USER_FILTER").unwrap_or("(|(mail={login})(uid={login}))".to_string()),
+            email_attr: env::var("LDAP_EMAIL_ATTR").unwrap_or("mail".to_string()),
+            name_attr: env::var("LDAP_NAME_ATTR").unwrap_or("cn".to_string()),
+        }
+    }
+
+    async fn connect(&self) -> Result<ldap3::Ldap, AuthProviderError> {
+        let settings = LdapConnSettings::new()
+            .set_conn_timeout(Duration::from_secs(5))
+            .set_starttls(self.starttls);
+        let (conn, ldap) = LdapConnAsync::with_settings(settings, &self.url).await?;
+        drive!(conn);
+        Ok(ldap)
+    }
+}
+
+#[async_trait]
+impl AuthProvider for LdapAuthProvider {
+    fn name(&self) -> &'static str {
+        "ldap"
+    }
+
+    async fn authenticate(&self, login: &str, password: &str) -> Result<Option<AuthenticatedIdentity>, AuthProviderError> {
+        // Bind dengan password kosong = "unauthenticated bind" yang selalu sukses di banyak server
+        if password.is_empty() || login.trim().is_empty() {
+            return Ok(None);
+        }
+
+        let mut ldap = self.connect().await?;
+
+        if let Some(bind_dn) = &self.bind_dn {
+            ldap.simple_bind(bind_dn, &self.bind_password).await?.success()?;
+        }
+
+        let filter = self.user_filter.replace("{login}", &ldap_escape(login.trim()));
+        let (entries, _) = ldap
+            .search(&self.base_dn, Scope::Subtree, &filter, vec![self.email_attr.as_str(), self.name_attr.as_str()])
+            .await?
+            .success()?;
+
+        // Login harus menunjuk tepat satu entry
+        if entries.len() != 1 {
+            let _ = ldap.unbind().await;
+            return Ok(None);
+        }
+        let entry = SearchEntry::construct(entries.into_iter().next().unwrap());
+
+        let bind = ldap.simple_bind(&entry.dn, password).await?;
+        let _ = ldap.unbind().await;
+        if bind.rc == LDAP_INVALID_CREDENTIALS {
+            return Ok(None);
+        }
+        bind.success()?;
+
+        let first = |attr: &str| entry.attrs.get(attr).and_then(|values| values.first()).cloned();
+        let Some(email) = first(&self.email_attr) else {
+            return Err(AuthProviderError::Config(format!("LDAP entry {} has no {} attribute", entry.dn, self.email_attr)));
+        };
+
+        Ok(Some(AuthenticatedIdentity::External(ExternalIdentity {
+            // Prefix supaya tidak bentrok dengan issuer OIDC di user_identities
+            issuer: format!("ldap:{}", self.url),
+            subject: entry.dn.clone(),
+            email,
+            display_name: first(&self.name_attr),
+        })))
+    }
+}
+
+// Rantai provider, dicoba sesuai urutan di AUTH_PROVIDERS
+pub struct AuthProviderChain {
+    db: DatabaseConnection,
+    providers: Vec<Arc<dyn AuthProvider>>,
+    create_external_users: bool,
+}
+
+impl AuthProviderChain {
+    pub fn from_env(db: &DatabaseConnection) -> Self {
+        let providers = env::var("AUTH_PROVIDERS")
+            .unwrap_or("database".to_string())
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(|name| -> Arc<dyn AuthProvider> {
+                match name {
+                    "database" => Arc::new(DbAuthProvider::new(db.clone())),
+                    "ldap" => Arc::new(LdapAuthProvider::from_env()),
+                    other => panic!("Unknown auth provider in AUTH_PROVIDERS: {}", other),
+                }
+            })
+            .collect();
+
+        Self {
+            db: db.clone(),
+            providers,
+            create_external_users: env::var("LDAP_CREATE_USERS").map(|v| v == "true").unwrap_or(false),
+        }
+    }
+
+    // Provider pertama yang menerima kredensial menang. Error di satu provider (mis. server
+    // LDAP mati) tidak menghentikan provider berikutnya; baru dikembalikan kalau tidak ada
+    // provider yang berhasil, supaya user tidak mendapat "password salah" padahal servernya down.
+    pub async fn authenticate(&self, login: &str, password: &str) -> Result<Option<user::Model>, AuthProviderError> {
+        let mut last_error = None;
+
+        for provider in &self.providers {
+            match provider.authenticate(login, password).await {
+                Ok(Some(AuthenticatedIdentity::Local(user_model))) => return Ok(Some(user_model)),
+                Ok(Some(AuthenticatedIdentity::External(identity))) => {
+                    match identity::find_or_link_user(&self.db, &identity, self.create_external_users).await? {
+                        Some(user_model) => return Ok(Some(user_model)),
+                        None => continue,
+                    }
+                }
+                Ok(None) => continue,
+                Err(err) => {
+                    eprintln!("Auth provider {} failed: {}", provider.name(), err);
+                    last_error = Some(err);
+                }
+            }
+        }
+
+        match last_error {
+            Some(err) => Err(err),
+            None => Ok(None),
+        }
+    }
+}
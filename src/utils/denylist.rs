@@ -0,0 +1,101 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sea_orm::*;
+use std::collections::HashMap;
+use std::env;
+use std::sync::{Arc, Mutex};
+use crate::entity::revoked_token;
+
+// Tempat menyimpan jti token yang sudah di-revoke sebelum exp-nya.
+// Implementasi dipilih lewat env TOKEN_DENYLIST ("database" atau "memory").
+#[async_trait]
+pub trait TokenDenylist: Send + Sync {
+    // `expires_at` = exp token aslinya, setelah itu entry boleh dibuang
+    async fn revoke(&self, jti: &str, expires_at: i64) -> Result<(), DbErr>;
+    async fn is_revoked(&self, jti: &str) -> Result<bool, DbErr>;
+    // Hapus entry yang sudah expired, return jumlah yang dihapus
+    async fn prune_expired(&self) -> Result<u64, DbErr>;
+}
+
+pub struct DbDenylist {
+    db: DatabaseConnection,
+}
+
+impl DbDenylist {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl TokenDenylist for DbDenylist {
+    async fn revoke(&self, jti: &str, expires_at: i64) -> Result<(), DbErr> {
+        let entry = revoked_token::ActiveModel {
+            id: NotSet,
+            jti: Set(jti.to_string()),
+            expires_at: Set(DateTime::from_timestamp(expires_at, 0).unwrap_or_default()),
+            created_at: NotSet,
+        };
+
+        // Token yang sama bisa di-logout dua kali, abaikan duplikat
+        revoked_token::Entity::insert(entry)
+            .on_conflict(
+                sea_query::OnConflict::column(revoked_token::Column::Jti)
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .do_nothing()
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn is_revoked(&self, jti: &str) -> Result<bool, DbErr> {
+        let count = revoked_token::Entity::find()
+            .filter(revoked_token::Column::Jti.eq(jti))
+            .count(&self.db)
+            .await?;
+        Ok(count > 0)
+    }
+
+    async fn prune_expired(&self) -> Result<u64, DbErr> {
+        let result = revoked_token::Entity::delete_many()
+            .filter(revoked_token::Column::ExpiresAt.lt(Utc::now()))
+            .exec(&self.db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+}
+
+// Versi in-memory, cocok untuk development atau deployment satu instance
+#[derive(Default)]
+pub struct MemoryDenylist {
+    entries: Mutex<HashMap<String, i64>>,
+}
+
+#[async_trait]
+impl TokenDenylist for MemoryDenylist {
+    async fn revoke(&self, jti: &str, expires_at: i64) -> Result<(), DbErr> {
+        self.entries.lock().unwrap().insert(jti.to_string(), expires_at);
+        Ok(())
+    }
+
+    async fn is_revoked(&self, jti: &str) -> Result<bool, DbErr> {
+        Ok(self.entries.lock().unwrap().contains_key(jti))
+    }
+
+    async fn prune_expired(&self) -> Result<u64, DbErr> {
+        let now = Utc::now().timestamp();
+        let mut entries = self.entries.lock().unwrap();
+        let before = entries.len();
+        entries.retain(|_, exp| *exp > now);
+        Ok((before - entries.len()) as u64)
+    }
+}
+
+pub fn from_env(db: &DatabaseConnection) -> Arc<dyn TokenDenylist> {
+    match env::var("TOKEN_DENYLIST").unwrap_or("database".to_string()).as_str() {
+        "memory" => Arc::new(MemoryDenylist::default()),
+        _ => Arc::new(DbDenylist::new(db.clone())),
+    }
+}
@@ -0,0 +1,98 @@
+use chrono::Utc;
+use sea_orm::*;
+use crate::entity::{user, user_identity};
+
+// Identitas user dari sistem luar (IdP OIDC, direktori LDAP)
+pub struct ExternalIdentity {
+    // Sumber identitas, mis. `iss` OIDC atau URL server LDAP
+    pub issuer: String,
+    // Id yang stabil di sumber tersebut, mis. `sub` OIDC atau DN LDAP
+    pub subject: String,
+    pub email: String,
+    pub display_name: Option<String>,
+}
+
+// User lokal dengan email yang sama hanya boleh ditautkan otomatis kalau akun itu memang
+// dibuat dari login eksternal (tanpa password lokal) dan bukan admin. Email di registrasi
+// lokal tidak diverifikasi, jadi tanpa aturan ini penyerang bisa mendaftar duluan dengan
+// email korban, dan IdP / entry LDAP dengan email yang cocok bisa mengambil alih admin.
+fn can_auto_link(user_model: &user::Model) -> bool {
+    user_model.password_hash.is_empty() && user_model.role != "admin"
+}
+
+// Cari user lokal untuk identitas eksternal lewat user_identities (issuer + subject).
+// Kalau belum tertaut, tautkan ke user dengan email yang sama (lihat `can_auto_link`);
+// kalau user belum ada dan `create_missing`, buat user baru tanpa password lokal.
+// None = tidak ada user yang boleh dipakai untuk identitas ini.
+pub async fn find_or_link_user(
+    db: &DatabaseConnection,
+    identity: &ExternalIdentity,
+    create_missing: bool,
+) -> Result<Option<user::Model>, DbErr> {
+    let now = Utc::now();
+    let email = identity.email.trim().to_lowercase();
+
+    let linked = user_identity::Entity::find()
+        .filter(user_identity::Column::Issuer.eq(&identity.issuer))
+        .filter(user_identity::Column::Subject.eq(&identity.subject))
+        .find_also_related(user::Entity)
+        .one(db)
+        .await?;
+
+    if let Some((linked, Some(user_model))) = linked {
+        let mut active: user_identity::ActiveModel = linked.into();
+        active.email = Set(Some(email));
+        active.last_login_at = Set(Some(now));
+        active.update(db).await?;
+        return Ok(Some(user_model));
+    }
+
+    let txn = db.begin().await?;
+
+    let existing = user::Entity::find()
+        .filter(user::Column::Email.eq(&email))
+        .one(&txn)
+        .await?;
+
+    let user_model = match existing {
+        Some(user_model) if can_auto_link(&user_model) => user_model,
+        Some(_) => return Ok(None),
+        None if create_missing => {
+            let username = identity
+                .display_name
+                .clone()
+                .unwrap_or_else(|| email.split('@').next().unwrap_or(&email).to_string());
+
+            user::ActiveModel {
+                id: NotSet,
+                username: Set(username.chars().take(100).collect()),
+                email: Set(email.clone()),
+                phone: NotSet,
+                phone_verified_at: NotSet,
+                // Kosong = tidak bisa login dengan password lokal
+                password_hash: Set(String::new()),
+                role: Set("user".to_string()),
+                token_version: NotSet,
+                created_at: NotSet,
+                updated_at: NotSet,
+            }
+            .insert(&txn)
+            .await?
+        }
+        None => return Ok(None),
+    };
+
+    user_identity::ActiveModel {
+        user_id: Set(user_model.id),
+        issuer: Set(identity.issuer.clone()),
+        subject: Set(identity.subject.clone()),
+        email: Set(Some(email)),
+        last_login_at: Set(Some(now)),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await?;
+
+    txn.commit().await?;
+    Ok(Some(user_model))
+}
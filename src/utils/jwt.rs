@@ -1,78 +1,221 @@
-use jsonwebtoken::{encode, decode, Header, Validation, EncodingKey, DecodingKey};
+use jsonwebtoken::{encode, decode, decode_header, Header, Validation};
+use jsonwebtoken::errors::{Error, ErrorKind};
 use serde::{Deserialize, Serialize};
 use chrono::{Utc, Duration};
 use std::env;
+use uuid::Uuid;
+use super::keys;
+use crate::config::auth;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Claims {
-    pub sub: String,      // user_id
+    pub sub: String,      // user_id, atau "client:<client_id>" untuk service account
     pub email: String,
     pub role: String,
+    pub iss: String,      // issuer
+    pub aud: String,      // audience
     pub exp: i64,         // expiry timestamp
+    pub nbf: i64,         // not before
     pub iat: i64,         // issued at
+    pub jti: String,      // unique token id
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sid: Option<String>, // session id (user_sessions.id)
     pub token_type: String, // "access" or "refresh"
+    pub ver: i32,         // users.token_version saat token diterbitkan
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub act: Option<Actor>, // admin yang sedang impersonate (RFC 8693)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scopes: Option<Vec<String>>, // None = akses penuh sesuai role
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>, // client OAuth yang meminta token (RFC 9068)
+}
+
+// Awalan `sub` untuk token service account (grant client_credentials)
+pub const CLIENT_SUBJECT_PREFIX: &str = "client:";
+
+// Role di token service account, tidak pernah cocok dengan role user
+pub const SERVICE_ROLE: &str = "service";
+
+// Siapa pemilik token: user biasa atau service account
+#[derive(Debug, Clone, PartialEq)]
+pub enum Principal {
+    User(i32),
+    Client(String),
+}
+
+// Claim `act` (RFC 8693): pihak yang sebenarnya bertindak atas nama `sub`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Actor {
+    pub sub: String,      // user_id admin
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
 }
 
 impl Claims {
-    pub fn new_access_token(user_id: i32, email: String, role: String) -> Self {
+    pub fn new_access_token(user_id: i32, email: String, role: String, token_version: i32, lifetime: Duration) -> Self {
         let now = Utc::now();
-        let exp = (now + Duration::hours(1)).timestamp(); // Access token: 1 jam
+        let exp = (now + lifetime).timestamp();
         
         Self {
             sub: user_id.to_string(),
             email,
             role,
+            iss: issuer(),
+            aud: audience(),
             exp,
+            nbf: now.timestamp(),
             iat: now.timestamp(),
+            jti: Uuid::new_v4().to_string(),
+            sid: None,
             token_type: "access".to_string(),
+            ver: token_version,
+            act: None,
+            scopes: None,
+            client_id: None,
         }
     }
 
-    pub fn new_refresh_token(user_id: i32, email: String, role: String) -> Self {
+    pub fn new_refresh_token(user_id: i32, email: String, role: String, token_version: i32, lifetime: Duration) -> Self {
         let now = Utc::now();
-        let exp = (now + Duration::days(7)).timestamp(); // Refresh token: 7 hari
+        let exp = (now + lifetime).timestamp();
         
         Self {
             sub: user_id.to_string(),
             email,
             role,
+            iss: issuer(),
+            aud: audience(),
             exp,
+            nbf: now.timestamp(),
             iat: now.timestamp(),
+            jti: Uuid::new_v4().to_string(),
+            sid: None,
             token_type: "refresh".to_string(),
+            ver: token_version,
+            act: None,
+            scopes: None,
+            client_id: None,
+        }
+    }
+
+    // Access token service account: tidak ada user, `sub` menunjuk client
+    pub fn new_client_token(client_id: &str, scopes: Vec<String>, lifetime: Duration) -> Self {
+        let now = Utc::now();
+        let exp = (now + lifetime).timestamp();
+
+        Self {
+            sub: format!("{}{}", CLIENT_SUBJECT_PREFIX, client_id),
+            email: String::new(),
+            role: SERVICE_ROLE.to_string(),
+            iss: issuer(),
+            aud: audience(),
+            exp,
+            nbf: now.timestamp(),
+            iat: now.timestamp(),
+            jti: Uuid::new_v4().to_string(),
+            sid: None,
+            token_type: "access".to_string(),
+            ver: 0,
+            act: None,
+            scopes: Some(scopes),
+            client_id: Some(client_id.to_string()),
+        }
+    }
+
+    // Umur token dalam detik (exp - iat), untuk `expires_in` dan max-age cookie
+    pub fn lifetime_secs(&self) -> i64 {
+        self.exp - self.iat
+    }
+
+    // true kalau token ini hasil impersonation oleh admin
+    pub fn is_impersonated(&self) -> bool {
+        self.act.is_some()
+    }
+
+    // Token tanpa daftar scope (login biasa) dianggap punya semua scope
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes
+            .as_ref()
+            .is_none_or(|scopes| scopes.iter().any(|s| s == scope))
+    }
+
+    // Parse `sub` kembali ke user_id, None untuk service account
+    pub fn user_id(&self) -> Option<i32> {
+        self.sub.parse().ok()
+    }
+
+    // client_id service account kalau token ini milik service account
+    pub fn service_client_id(&self) -> Option<&str> {
+        self.sub.strip_prefix(CLIENT_SUBJECT_PREFIX)
+    }
+
+    pub fn principal(&self) -> Option<Principal> {
+        match self.service_client_id() {
+            Some(client_id) => Some(Principal::Client(client_id.to_string())),
+            None => self.user_id().map(Principal::User),
         }
     }
 }
 
-pub fn generate_access_token(user_id: i32, email: String, role: String) -> Result<String, jsonwebtoken::errors::Error> {
-    let claims = Claims::new_access_token(user_id, email, role);
-    let secret = env::var("JWT_SECRET").unwrap_or_else(|_| "your-secret-key-change-in-production".to_string());
-    
-    encode(
-        &Header::default(),
-        &claims,
-        &EncodingKey::from_secret(secret.as_bytes()),
-    )
+// Nilai `iss` untuk token yang kita terbitkan, bisa diatur lewat JWT_ISSUER
+pub fn issuer() -> String {
+    env::var("JWT_ISSUER").unwrap_or("actix_server".to_string())
 }
 
-pub fn generate_refresh_token(user_id: i32, email: String, role: String) -> Result<String, jsonwebtoken::errors::Error> {
-    let claims = Claims::new_refresh_token(user_id, email, role);
-    let secret = env::var("JWT_SECRET").unwrap_or_else(|_| "your-secret-key-change-in-production".to_string());
+// Nilai `aud` yang wajib ada di token, bisa diatur lewat JWT_AUDIENCE
+pub fn audience() -> String {
+    env::var("JWT_AUDIENCE").unwrap_or("actix_server".to_string())
+}
+
+pub fn encode_claims(claims: &Claims) -> Result<String, jsonwebtoken::errors::Error> {
+    let key = keys::current();
+    let mut header = Header::new(key.algorithm);
+    header.kid = Some(key.kid.clone());
     
-    encode(
-        &Header::default(),
-        &claims,
-        &EncodingKey::from_secret(secret.as_bytes()),
-    )
+    encode(&header, claims, &key.encoding)
+}
+
+#[allow(dead_code)]
+pub fn generate_access_token(user_id: i32, email: String, role: String, token_version: i32) -> Result<String, jsonwebtoken::errors::Error> {
+    let lifetime = Duration::seconds(auth::access_token_ttl_secs());
+    encode_claims(&Claims::new_access_token(user_id, email, role, token_version, lifetime))
+}
+
+#[allow(dead_code)]
+pub fn generate_refresh_token(user_id: i32, email: String, role: String, token_version: i32) -> Result<String, jsonwebtoken::errors::Error> {
+    let lifetime = Duration::seconds(auth::refresh_token_ttl_secs(false));
+    encode_claims(&Claims::new_refresh_token(user_id, email, role, token_version, lifetime))
 }
 
 pub fn validate_token(token: &str) -> Result<Claims, jsonwebtoken::errors::Error> {
-    let secret = env::var("JWT_SECRET").unwrap_or_else(|_| "your-secret-key-change-in-production".to_string());
+    // Pilih key berdasarkan `kid`, key yang sudah retired tidak ada di ring
+    let header = decode_header(token)?;
+    let key = keys::verification_key(header.kid.as_deref())
+        .ok_or_else(|| Error::from(ErrorKind::InvalidToken))?;
+    
+    let mut validation = Validation::new(key.algorithm);
+    validation.set_issuer(&[issuer()]);
+    validation.set_audience(&[audience()]);
+    validation.set_required_spec_claims(&["exp", "nbf", "iss", "aud", "sub"]);
+    validation.validate_nbf = true;
     
     let token_data = decode::<Claims>(
         token,
-        &DecodingKey::from_secret(secret.as_bytes()),
-        &Validation::default(),
+        &key.decoding,
+        &validation,
     )?;
     
     Ok(token_data.claims)
 }
+
+// Pesan error untuk client berdasarkan alasan token ditolak
+pub fn rejection_message(err: &Error) -> &'static str {
+    match err.kind() {
+        ErrorKind::ExpiredSignature => "Token has expired",
+        ErrorKind::ImmatureSignature => "Token is not yet valid",
+        ErrorKind::InvalidIssuer => "Invalid token issuer",
+        ErrorKind::InvalidAudience => "Invalid token audience",
+        ErrorKind::MissingRequiredClaim(_) => "Token is missing required claims",
+        _ => "Invalid token",
+    }
+}
@@ -0,0 +1,435 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Duration, Utc};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey};
+use jsonwebtoken::jwk::{
+    AlgorithmParameters, CommonParameters, EllipticCurve, EllipticCurveKeyParameters,
+    EllipticCurveKeyType, Jwk, KeyAlgorithm, OctetKeyPairParameters, OctetKeyPairType,
+    PublicKeyUse, RSAKeyParameters, RSAKeyType,
+};
+use serde::{Deserialize, Serialize};
+use simple_asn1::ASN1Block;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::str::FromStr;
+use std::sync::{Arc, OnceLock, RwLock};
+
+// Key ring untuk sign & verify JWT.
+//
+// Mode key ring (direkomendasikan): JWT_KEYRING_PATH menunjuk ke file JSON berisi
+// daftar key. Token di-sign dengan key berstatus "active" dan diberi header `kid`,
+// verifikasi menerima semua key yang belum retired.
+//
+// Mode single key (tanpa JWT_KEYRING_PATH), dipilih lewat env:
+//   JWT_ALGORITHM         HS256 (default), RS256, ES256 atau EdDSA
+//   JWT_KEY_ID            kid untuk key ini (default "default")
+//   JWT_SECRET            shared secret untuk HS256
+//   JWT_PRIVATE_KEY_PATH  PEM private key (PKCS#8, atau PKCS#1 untuk RSA)
+//   JWT_PUBLIC_KEY_PATH   PEM public key (SubjectPublicKeyInfo)
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyStatus {
+    // Sudah dipublish di JWKS tapi belum dipakai sign, supaya cache JWKS di service lain sempat update
+    Pending,
+    // Satu-satunya key yang dipakai untuk sign
+    Active,
+    // Key lama, masih diterima untuk verifikasi sampai `retire_at`
+    Retiring,
+    Retired,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct KeyEntry {
+    pub kid: String,
+    pub algorithm: Algorithm,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub private_key_path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_key_path: Option<String>,
+    pub status: KeyStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retire_at: Option<DateTime<Utc>>,
+}
+
+impl KeyEntry {
+    pub fn accepts_verification(&self, now: DateTime<Utc>) -> bool {
+        match self.status {
+            KeyStatus::Pending | KeyStatus::Active => true,
+            KeyStatus::Retiring => self.retire_at.is_none_or(|at| at > now),
+            KeyStatus::Retired => false,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct KeyRingFile {
+    pub keys: Vec<KeyEntry>,
+}
+
+pub struct SigningKey {
+    pub kid: String,
+    pub algorithm: Algorithm,
+    pub encoding: EncodingKey,
+    pub decoding: DecodingKey,
+    // Hanya ada untuk key asymmetric, secret HS256 tidak pernah dipublish
+    pub jwk: Option<Jwk>,
+}
+
+struct KeyRing {
+    path: Option<String>,
+    entries: Vec<KeyEntry>,
+    loaded: HashMap<String, Arc<SigningKey>>,
+    active: Arc<SigningKey>,
+}
+
+static KEY_RING: OnceLock<RwLock<KeyRing>> = OnceLock::new();
+
+// Key ring dimuat sekali saat pertama dipakai. Konfigurasi yang salah langsung panic,
+// main() memanggil init() saat startup supaya error muncul sebelum server jalan.
+fn ring() -> &'static RwLock<KeyRing> {
+    KEY_RING.get_or_init(|| {
+        let ring = load_from_env().unwrap_or_else(|e| panic!("Invalid JWT key configuration: {}", e));
+        RwLock::new(ring)
+    })
+}
+
+pub fn init() {
+    ring();
+}
+
+// Key yang dipakai untuk sign token baru
+pub fn current() -> Arc<SigningKey> {
+    ring().read().unwrap().active.clone()
+}
+
+// Key untuk verifikasi berdasarkan `kid` di header token.
+// Token lama tanpa `kid` diverifikasi dengan key yang sedang active.
+pub fn verification_key(kid: Option<&str>) -> Option<Arc<SigningKey>> {
+    let ring = ring().read().unwrap();
+    let kid = match kid {
+        Some(kid) => kid,
+        None => return Some(ring.active.clone()),
+    };
+
+    let now = Utc::now();
+    ring.entries
+        .iter()
+        .find(|entry| entry.kid == kid && entry.accepts_verification(now))
+        .and_then(|entry| ring.loaded.get(&entry.kid).cloned())
+}
+
+// Semua public key yang masih diterima, untuk /.well-known/jwks.json
+pub fn public_jwks() -> Vec<Jwk> {
+    let ring = ring().read().unwrap();
+    let now = Utc::now();
+    ring.entries
+        .iter()
+        .filter(|entry| entry.accepts_verification(now))
+        .filter_map(|entry| ring.loaded.get(&entry.kid))
+        .filter_map(|key| key.jwk.clone())
+        .collect()
+}
+
+pub fn entries() -> Vec<KeyEntry> {
+    ring().read().unwrap().entries.clone()
+}
+
+// Muat ulang key ring dari file, dipanggil berkala supaya semua instance sinkron
+pub fn reload() -> Result<(), String> {
+    let path = match ring().read().unwrap().path.clone() {
+        Some(path) => path,
+        None => return Ok(()),
+    };
+    let fresh = load_ring_file(&path)?;
+    *ring().write().unwrap() = fresh;
+    Ok(())
+}
+
+// Jadikan `kid` key active. Key active sebelumnya menjadi retiring dan masih diterima
+// untuk verifikasi selama `grace`, supaya user yang sudah login tidak ter-logout.
+pub fn promote(kid: &str, grace: Duration) -> Result<(), String> {
+    let path = ring_path()?;
+    let mut file = read_ring_file(&path)?;
+    let now = Utc::now();
+
+    let target = file
+        .keys
+        .iter()
+        .position(|entry| entry.kid == kid)
+        .ok_or_else(|| format!("key {} not found", kid))?;
+    if file.keys[target].status == KeyStatus::Active {
+        return Err(format!("key {} is already active", kid));
+    }
+    if file.keys[target].status == KeyStatus::Retired {
+        return Err(format!("key {} is retired", kid));
+    }
+    // Pastikan key baru bisa dimuat sebelum dipakai sign
+    load_entry(&file.keys[target])?;
+
+    for entry in file.keys.iter_mut() {
+        if entry.status == KeyStatus::Active {
+            entry.status = KeyStatus::Retiring;
+            entry.retire_at = Some(now + grace);
+        }
+    }
+    file.keys[target].status = KeyStatus::Active;
+    file.keys[target].retire_at = None;
+
+    write_ring_file(&path, &file)?;
+    reload()
+}
+
+// Retire key sekarang juga, token yang di-sign dengan key ini langsung ditolak
+pub fn retire(kid: &str) -> Result<(), String> {
+    let path = ring_path()?;
+    let mut file = read_ring_file(&path)?;
+
+    let entry = file
+        .keys
+        .iter_mut()
+        .find(|entry| entry.kid == kid)
+        .ok_or_else(|| format!("key {} not found", kid))?;
+    if entry.status == KeyStatus::Active {
+        return Err("cannot retire the active key, promote another key first".to_string());
+    }
+    entry.status = KeyStatus::Retired;
+    entry.retire_at = None;
+
+    write_ring_file(&path, &file)?;
+    reload()
+}
+
+// Ubah key retiring yang grace period-nya sudah lewat menjadi retired
+pub fn sweep_retired() -> Result<usize, String> {
+    let path = ring_path()?;
+    let mut file = read_ring_file(&path)?;
+    let now = Utc::now();
+
+    let mut swept = 0;
+    for entry in file.keys.iter_mut() {
+        if entry.status == KeyStatus::Retiring && !entry.accepts_verification(now) {
+            entry.status = KeyStatus::Retired;
+            entry.retire_at = None;
+            swept += 1;
+        }
+    }
+
+    if swept > 0 {
+        write_ring_file(&path, &file)?;
+        reload()?;
+    }
+    Ok(swept)
+}
+
+pub fn default_grace() -> Duration {
+    let secs = env::var("JWT_KEY_GRACE_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(7 * 24 * 3600); // sama dengan umur refresh token
+    Duration::seconds(secs)
+}
+
+fn ring_path() -> Result<String, String> {
+    env::var("JWT_KEYRING_PATH").map_err(|_| "JWT_KEYRING_PATH is not configured".to_string())
+}
+
+fn load_from_env() -> Result<KeyRing, String> {
+    if let Ok(path) = env::var("JWT_KEYRING_PATH") {
+        return load_ring_file(&path);
+    }
+
+    let alg_name = env::var("JWT_ALGORITHM").unwrap_or("HS256".to_string());
+    let algorithm = Algorithm::from_str(&alg_name).map_err(|_| format!("unknown JWT_ALGORITHM {}", alg_name))?;
+
+    let entry = KeyEntry {
+        kid: env::var("JWT_KEY_ID").unwrap_or("default".to_string()),
+        algorithm,
+        secret: Some(env::var("JWT_SECRET").unwrap_or_else(|_| "your-secret-key-change-in-production".to_string())),
+        private_key_path: env::var("JWT_PRIVATE_KEY_PATH").ok(),
+        public_key_path: env::var("JWT_PUBLIC_KEY_PATH").ok(),
+        status: KeyStatus::Active,
+        retire_at: None,
+    };
+    build_ring(None, vec![entry])
+}
+
+fn load_ring_file(path: &str) -> Result<KeyRing, String> {
+    let file = read_ring_file(path)?;
+    build_ring(Some(path.to_string()), file.keys)
+}
+
+fn read_ring_file(path: &str) -> Result<KeyRingFile, String> {
+    let raw = fs::read_to_string(path).map_err(|e| format!("cannot read {}: {}", path, e))?;
+    serde_json::from_str(&raw).map_err(|e| format!("invalid key ring {}: {}", path, e))
+}
+
+// Tulis ke file sementara lalu rename, supaya instance lain tidak membaca file setengah jadi
+fn write_ring_file(path: &str, file: &KeyRingFile) -> Result<(), String> {
+    let raw = serde_json::to_string_pretty(file).map_err(|e| e.to_string())?;
+    let tmp = format!("{}.tmp", path);
+    fs::write(&tmp, raw).map_err(|e| format!("cannot write {}: {}", tmp, e))?;
+    fs::rename(&tmp, path).map_err(|e| format!("cannot write {}: {}", path, e))
+}
+
+fn build_ring(path: Option<String>, entries: Vec<KeyEntry>) -> Result<KeyRing, String> {
+    let now = Utc::now();
+    let mut loaded = HashMap::new();
+    for entry in entries.iter().filter(|entry| entry.accepts_verification(now)) {
+        if loaded.contains_key(&entry.kid) {
+            return Err(format!("duplicate kid {}", entry.kid));
+        }
+        let key = load_entry(entry).map_err(|e| format!("key {}: {}", entry.kid, e))?;
+        loaded.insert(entry.kid.clone(), Arc::new(key));
+    }
+
+    let mut active = entries.iter().filter(|entry| entry.status == KeyStatus::Active);
+    let active_kid = match (active.next(), active.next()) {
+        (Some(entry), None) => entry.kid.clone(),
+        (None, _) => return Err("key ring has no active key".to_string()),
+        (Some(_), Some(_)) => return Err("key ring has more than one active key".to_string()),
+    };
+    let active = loaded[&active_kid].clone();
+
+    Ok(KeyRing { path, entries, loaded, active })
+}
+
+fn load_entry(entry: &KeyEntry) -> Result<SigningKey, String> {
+    if entry.algorithm == Algorithm::HS256 {
+        let secret = entry.secret.as_ref().ok_or("HS256 key needs a secret")?;
+        return Ok(SigningKey {
+            kid: entry.kid.clone(),
+            algorithm: entry.algorithm,
+            encoding: EncodingKey::from_secret(secret.as_bytes()),
+            decoding: DecodingKey::from_secret(secret.as_bytes()),
+            jwk: None,
+        });
+    }
+
+    let private_pem = read_pem_file(entry.private_key_path.as_deref(), "private_key_path")?;
+    let public_pem = read_pem_file(entry.public_key_path.as_deref(), "public_key_path")?;
+    load_asymmetric(entry.algorithm, &private_pem, &public_pem, entry.kid.clone())
+}
+
+fn read_pem_file(path: Option<&str>, field: &str) -> Result<Vec<u8>, String> {
+    let path = path.ok_or_else(|| format!("{} must be set", field))?;
+    fs::read(path).map_err(|e| format!("cannot read {}: {}", path, e))
+}
+
+fn load_asymmetric(
+    algorithm: Algorithm,
+    private_pem: &[u8],
+    public_pem: &[u8],
+    kid: String,
+) -> Result<SigningKey, String> {
+    let (encoding, decoding) = match algorithm {
+        Algorithm::RS256 => (
+            EncodingKey::from_rsa_pem(private_pem),
+            DecodingKey::from_rsa_pem(public_pem),
+        ),
+        Algorithm::ES256 => (
+            EncodingKey::from_ec_pem(private_pem),
+            DecodingKey::from_ec_pem(public_pem),
+        ),
+        Algorithm::EdDSA => (
+            EncodingKey::from_ed_pem(private_pem),
+            DecodingKey::from_ed_pem(public_pem),
+        ),
+        other => return Err(format!("unsupported algorithm {:?}", other)),
+    };
+
+    let encoding = encoding.map_err(|e| format!("invalid private key: {}", e))?;
+    let decoding = decoding.map_err(|e| format!("invalid public key: {}", e))?;
+    let jwk = public_jwk(algorithm, public_pem, kid.clone())?;
+
+    Ok(SigningKey {
+        kid,
+        algorithm,
+        encoding,
+        decoding,
+        jwk: Some(jwk),
+    })
+}
+
+// Bangun JWK dari public key PEM (SubjectPublicKeyInfo):
+//   SEQUENCE { SEQUENCE { algorithm OID, params }, BIT STRING subjectPublicKey }
+fn public_jwk(algorithm: Algorithm, public_pem: &[u8], kid: String) -> Result<Jwk, String> {
+    let pem = pem::parse(public_pem).map_err(|e| format!("invalid public key PEM: {}", e))?;
+    if pem.tag() != "PUBLIC KEY" {
+        return Err(format!("expected a PUBLIC KEY PEM, found {}", pem.tag()));
+    }
+
+    let blocks = simple_asn1::from_der(pem.contents()).map_err(|e| format!("invalid public key DER: {}", e))?;
+    let key_bits = match blocks.first() {
+        Some(ASN1Block::Sequence(_, items)) => match items.get(1) {
+            Some(ASN1Block::BitString(_, _, bits)) => bits.clone(),
+            _ => return Err("public key is missing its BIT STRING".to_string()),
+        },
+        _ => return Err("public key is not a SubjectPublicKeyInfo".to_string()),
+    };
+
+    let (key_algorithm, params) = match algorithm {
+        Algorithm::RS256 => {
+            // BIT STRING berisi RSAPublicKey ::= SEQUENCE { modulus, publicExponent }
+            let inner = simple_asn1::from_der(&key_bits).map_err(|e| format!("invalid RSA public key: {}", e))?;
+            let (n, e) = match inner.first() {
+                Some(ASN1Block::Sequence(_, ints)) => match (ints.first(), ints.get(1)) {
+                    (Some(ASN1Block::Integer(_, n)), Some(ASN1Block::Integer(_, e))) => {
+                        (n.to_bytes_be().1, e.to_bytes_be().1)
+                    }
+                    _ => return Err("invalid RSA public key".to_string()),
+                },
+                _ => return Err("invalid RSA public key".to_string()),
+            };
+            (
+                KeyAlgorithm::RS256,
+                AlgorithmParameters::RSA(RSAKeyParameters {
+                    key_type: RSAKeyType::RSA,
+                    n: URL_SAFE_NO_PAD.encode(n),
+                    e: URL_SAFE_NO_PAD.encode(e),
+                }),
+            )
+        }
+        Algorithm::ES256 => {
+            // Uncompressed point: 0x04 || x (32 byte) || y (32 byte)
+            if key_bits.len() != 65 || key_bits[0] != 0x04 {
+                return Err("ES256 public key must be an uncompressed P-256 point".to_string());
+            }
+            (
+                KeyAlgorithm::ES256,
+                AlgorithmParameters::EllipticCurve(EllipticCurveKeyParameters {
+                    key_type: EllipticCurveKeyType::EC,
+                    curve: EllipticCurve::P256,
+                    x: URL_SAFE_NO_PAD.encode(&key_bits[1..33]),
+                    y: URL_SAFE_NO_PAD.encode(&key_bits[33..]),
+                }),
+            )
+        }
+        Algorithm::EdDSA => {
+            if key_bits.len() != 32 {
+                return Err("EdDSA public key must be a 32 byte Ed25519 key".to_string());
+            }
+            (
+                KeyAlgorithm::EdDSA,
+                AlgorithmParameters::OctetKeyPair(OctetKeyPairParameters {
+                    key_type: OctetKeyPairType::OctetKeyPair,
+                    curve: EllipticCurve::Ed25519,
+                    x: URL_SAFE_NO_PAD.encode(&key_bits),
+                }),
+            )
+        }
+        other => return Err(format!("unsupported algorithm {:?}", other)),
+    };
+
+    Ok(Jwk {
+        common: CommonParameters {
+            public_key_use: Some(PublicKeyUse::Signature),
+            key_algorithm: Some(key_algorithm),
+            key_id: Some(kid),
+            ..Default::default()
+        },
+        algorithm: params,
+    })
+}
@@ -0,0 +1,206 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use lettre::message::{header::ContentType, Mailbox};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use std::env;
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+// Pengiriman email (magic link, kode OTP, notifikasi keamanan) lewat transport yang bisa diganti.
+// MAIL_TRANSPORT wajib diisi, tidak ada default:
+//   smtp  kirim lewat server SMTP (production)
+//           SMTP_HOST      wajib
+//           SMTP_PORT      default 587 (starttls), 465 (tls) atau 25 (none)
+//           SMTP_TLS       "starttls" (default), "tls" (implicit TLS) atau "none" (hanya untuk relay lokal)
+//           SMTP_USERNAME, SMTP_PASSWORD  kosong = tanpa autentikasi
+//   log   tulis email ke stdout, HANYA untuk development
+//   file  simpan setiap email sebagai file .eml di MAIL_DIR (default "mail"),
+//         HANYA untuk development / test
+// Transport log dan file membocorkan link login dan kode OTP ke log / disk, jadi startup
+// mencetak peringatan keras kalau dipilih.
+// MAIL_FROM: alamat pengirim (default no-reply@localhost)
+
+pub struct Email {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+#[derive(Debug)]
+pub enum MailError {
+    Io(std::io::Error),
+    Address(lettre::address::AddressError),
+    Message(lettre::error::Error),
+    Smtp(lettre::transport::smtp::Error),
+}
+
+impl fmt::Display for MailError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MailError::Io(err) => write!(f, "Mail IO error: {}", err),
+            MailError::Address(err) => write!(f, "Invalid email address: {}", err),
+            MailError::Message(err) => write!(f, "Failed to build email: {}", err),
+            MailError::Smtp(err) => write!(f, "SMTP error: {}", err),
+        }
+    }
+}
+
+impl From<std::io::Error> for MailError {
+    fn from(err: std::io::Error) -> Self {
+        MailError::Io(err)
+    }
+}
+
+impl From<lettre::address::AddressError> for MailError {
+    fn from(err: lettre::address::AddressError) -> Self {
+        MailError::Address(err)
+    }
+}
+
+impl From<lettre::error::Error> for MailError {
+    fn from(err: lettre::error::Error) -> Self {
+        MailError::Message(err)
+    }
+}
+
+impl From<lettre::transport::smtp::Error> for MailError {
+    fn from(err: lettre::transport::smtp::Error) -> Self {
+        MailError::Smtp(err)
+    }
+}
+
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    async fn send(&self, email: &Email) -> Result<(), MailError>;
+}
+
+pub struct SmtpMailer {
+    from: Mailbox,
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+}
+
+impl SmtpMailer {
+    pub fn from_env(from: &str) -> Self {
+        let host = env::var("SMTP_HOST").expect("SMTP_HOST must be set when MAIL_TRANSPORT=smtp");
+        let tls = env::var("SMTP_TLS").unwrap_or("starttls".to_string());
+
+        let builder = match tls.as_str() {
+            "tls" => AsyncSmtpTransport::<Tokio1Executor>::relay(&host),
+            "starttls" => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&host),
+            "none" => Ok(AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&host)),
+            other => panic!("Unknown SMTP_TLS: {} (expected starttls, tls or none)", other),
+        };
+        let mut builder = builder.unwrap_or_else(|err| panic!("Invalid SMTP_HOST {}: {}", host, err));
+
+        if let Some(port) = env::var("SMTP_PORT").ok().and_then(|v| v.parse().ok()) {
+            builder = builder.port(port);
+        }
+
+        if let Ok(username) = env::var("SMTP_USERNAME")
+            && !username.is_empty()
+        {
+            let password = env::var("SMTP_PASSWORD").unwrap_or_default();
+            builder = builder.credentials(Credentials::new(username, password));
+        }
+
+        Self {
+            from: from.parse().expect("MAIL_FROM must be a valid email address"),
+            transport: builder.build(),
+        }
+    }
+}
+
+#[async_trait]
+impl Mailer for SmtpMailer {
+    async fn send(&self, email: &Email) -> Result<(), MailError> {
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(email.to.parse()?)
+            .subject(email.subject.as_str())
+            .header(ContentType::TEXT_PLAIN)
+            .body(email.body.clone())?;
+
+        self.transport.send(message).await?;
+        Ok(())
+    }
+}
+
+pub struct LogMailer {
+    from: String,
+}
+
+#[async_trait]
+impl Mailer for LogMailer {
+    async fn send(&self, email: &Email) -> Result<(), MailError> {
+        println!(
+            "[mail] from={} to={} subject={:?}\n{}",
+            self.from, email.to, email.subject, email.body
+        );
+        Ok(())
+    }
+}
+
+pub struct FileMailer {
+    from: String,
+    dir: PathBuf,
+}
+
+#[async_trait]
+impl Mailer for FileMailer {
+    async fn send(&self, email: &Email) -> Result<(), MailError> {
+        tokio::fs::create_dir_all(&self.dir).await?;
+
+        let now = Utc::now();
+        let path = self.dir.join(format!("{}-{}.eml", now.format("%Y%m%dT%H%M%S%.3f"), uuid::Uuid::new_v4()));
+        let message = format!(
+            "From: {}\r\nTo: {}\r\nSubject: {}\r\nDate: {}\r\nContent-Type: text/plain; charset=utf-8\r\n\r\n{}\r\n",
+            header_value(&self.from),
+            header_value(&email.to),
+            header_value(&email.subject),
+            now.to_rfc2822(),
+            email.body
+        );
+
+        tokio::fs::write(path, message).await?;
+        Ok(())
+    }
+}
+
+// Cegah header injection lewat CR/LF di nilai header
+fn header_value(value: &str) -> String {
+    value.replace(['\r', '\n'], " ")
+}
+
+fn warn_dev_only(transport: &str) {
+    eprintln!(
+        "WARNING: MAIL_TRANSPORT={} is for development only. Sign-in links, OTP codes and \
+         security notifications are written to {} instead of being delivered. \
+         Use MAIL_TRANSPORT=smtp in production.",
+        transport,
+        if transport == "file" { "disk" } else { "stdout" }
+    );
+}
+
+pub fn from_env() -> Arc<dyn Mailer> {
+    let from = env::var("MAIL_FROM").unwrap_or("no-reply@localhost".to_string());
+    let transport = env::var("MAIL_TRANSPORT")
+        .expect("MAIL_TRANSPORT must be set: smtp, or log / file for development");
+
+    match transport.as_str() {
+        "smtp" => Arc::new(SmtpMailer::from_env(&from)),
+        "log" => {
+            warn_dev_only(&transport);
+            Arc::new(LogMailer { from })
+        }
+        "file" => {
+            warn_dev_only(&transport);
+            Arc::new(FileMailer {
+                from,
+                dir: PathBuf::from(env::var("MAIL_DIR").unwrap_or("mail".to_string())),
+            })
+        }
+        other => panic!("Unknown MAIL_TRANSPORT: {} (expected smtp, log or file)", other),
+    }
+}
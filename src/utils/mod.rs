@@ -1,2 +1,19 @@
 pub mod jwt;
 pub mod hash;
+pub mod denylist;
+pub mod keys;
+pub mod token_version;
+pub mod session_revocation;
+pub mod session_store;
+pub mod api_key;
+pub mod personal_token;
+pub mod scope;
+pub mod pkce;
+pub mod oidc;
+pub mod identity;
+pub mod auth_provider;
+pub mod mailer;
+pub mod otp;
+pub mod secret_box;
+pub mod totp;
+pub mod webauthn;
@@ -0,0 +1,248 @@
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+use jsonwebtoken::jwk::JwkSet;
+use serde::Deserialize;
+use std::env;
+use std::fmt;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+// Login lewat IdP eksternal (OpenID Connect, authorization code + PKCE).
+// Aktif kalau OIDC_ISSUER diisi:
+//   OIDC_ISSUER            URL issuer, discovery di <issuer>/.well-known/openid-configuration
+//                          (http diperbolehkan supaya bisa dites dengan mock IdP lokal)
+//   OIDC_CLIENT_ID         client_id kita di IdP
+//   OIDC_CLIENT_SECRET     client secret (kosong untuk public client)
+//   OIDC_REDIRECT_URI      URL callback kita, mis. http://localhost:8080/api/auth/oidc/callback
+//   OIDC_SCOPES            default "openid email profile"
+//   OIDC_ALLOWED_DOMAINS   domain email yang boleh login, dipisah koma (kosong = semua)
+
+// Discovery & JWKS di-cache, JWKS diambil ulang kalau ada kid yang belum dikenal
+const DISCOVERY_TTL: Duration = Duration::from_secs(3600);
+
+#[derive(Debug)]
+pub enum OidcError {
+    Http(reqwest::Error),
+    Provider(String),
+    InvalidIdToken(String),
+}
+
+impl fmt::Display for OidcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OidcError::Http(err) => write!(f, "OIDC request failed: {}", err),
+            OidcError::Provider(msg) => write!(f, "OIDC provider error: {}", msg),
+            OidcError::InvalidIdToken(msg) => write!(f, "Invalid ID token: {}", msg),
+        }
+    }
+}
+
+impl From<reqwest::Error> for OidcError {
+    fn from(err: reqwest::Error) -> Self {
+        OidcError::Http(err)
+    }
+}
+
+#[derive(Clone, Deserialize)]
+struct Discovery {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+}
+
+#[derive(Deserialize)]
+struct TokenEndpointResponse {
+    id_token: Option<String>,
+}
+
+// Claim ID token yang kita pakai
+#[derive(Debug, Deserialize)]
+pub struct IdTokenClaims {
+    pub iss: String,
+    pub sub: String,
+    pub email: Option<String>,
+    #[serde(default)]
+    pub email_verified: bool,
+    pub name: Option<String>,
+    pub preferred_username: Option<String>,
+    pub nonce: Option<String>,
+}
+
+pub struct OidcClient {
+    issuer: String,
+    client_id: String,
+    client_secret: Option<String>,
+    redirect_uri: String,
+    scopes: String,
+    allowed_domains: Vec<String>,
+    http: reqwest::Client,
+    discovery: RwLock<Option<(Discovery, Instant)>>,
+    jwks: RwLock<Option<JwkSet>>,
+}
+
+impl OidcClient {
+    // None kalau OIDC tidak dikonfigurasi
+    pub fn from_env() -> Option<Self> {
+        let issuer = env::var("OIDC_ISSUER").ok().filter(|v| !v.is_empty())?;
+        let client_id = env::var("OIDC_CLIENT_ID").expect("OIDC_CLIENT_ID must be set when OIDC_ISSUER is set");
+        let redirect_uri = env::var("OIDC_REDIRECT_URI").expect("OIDC_REDIRECT_URI must be set when OIDC_ISSUER is set");
+
+        Some(Self {
+            issuer: issuer.trim_end_matches('/').to_string(),
+            client_id,
+            client_secret: env::var("OIDC_CLIENT_SECRET").ok().filter(|v| !v.is_empty()),
+            redirect_uri,
+            scopes: env::var("OIDC_SCOPES").unwrap_or("openid email profile".to_string()),
+            allowed_domains: env::var("OIDC_ALLOWED_DOMAINS")
+                .unwrap_or_default()
+                .split(',')
+                .map(|d| d.trim().to_lowercase())
+                .filter(|d| !d.is_empty())
+                .collect(),
+            http: reqwest::Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()
+                .expect("Failed to build HTTP client"),
+            discovery: RwLock::new(None),
+            jwks: RwLock::new(None),
+        })
+    }
+
+    // Email yang boleh login lewat IdP ini
+    pub fn is_allowed_email(&self, email: &str) -> bool {
+        if self.allowed_domains.is_empty() {
+            return true;
+        }
+        email
+            .rsplit_once('@')
+            .is_some_and(|(_, domain)| self.allowed_domains.iter().any(|d| d == &domain.to_lowercase()))
+    }
+
+    async fn discovery(&self) -> Result<Discovery, OidcError> {
+        if let Some((discovery, fetched_at)) = self.discovery.read().unwrap().as_ref()
+            && fetched_at.elapsed() < DISCOVERY_TTL
+        {
+            return Ok(discovery.clone());
+        }
+
+        let url = format!("{}/.well-known/openid-configuration", self.issuer);
+        let discovery: Discovery = self.http.get(url).send().await?.error_for_status()?.json().await?;
+
+        // OIDC Discovery 4.3: issuer di dokumen harus sama persis dengan yang dikonfigurasi
+        if discovery.issuer.trim_end_matches('/') != self.issuer {
+            return Err(OidcError::Provider(format!("issuer mismatch: {}", discovery.issuer)));
+        }
+
+        *self.discovery.write().unwrap() = Some((discovery.clone(), Instant::now()));
+        Ok(discovery)
+    }
+
+    // URL authorize IdP tempat browser diarahkan
+    pub async fn authorization_url(&self, state: &str, nonce: &str, code_challenge: &str) -> Result<String, OidcError> {
+        let discovery = self.discovery().await?;
+        let query = serde_urlencoded::to_string([
+            ("response_type", "code"),
+            ("client_id", self.client_id.as_str()),
+            ("redirect_uri", self.redirect_uri.as_str()),
+            ("scope", self.scopes.as_str()),
+            ("state", state),
+            ("nonce", nonce),
+            ("code_challenge", code_challenge),
+            ("code_challenge_method", "S256"),
+        ])
+        .map_err(|err| OidcError::Provider(err.to_string()))?;
+
+        let separator = if discovery.authorization_endpoint.contains('?') { '&' } else { '?' };
+        Ok(format!("{}{}{}", discovery.authorization_endpoint, separator, query))
+    }
+
+    // Tukar authorization code dengan token di IdP, return ID token mentah
+    pub async fn exchange_code(&self, code: &str, code_verifier: &str) -> Result<String, OidcError> {
+        let discovery = self.discovery().await?;
+        let form = [
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", self.redirect_uri.as_str()),
+            ("code_verifier", code_verifier),
+            ("client_id", self.client_id.as_str()),
+        ];
+
+        let mut request = self.http.post(&discovery.token_endpoint).form(&form);
+        if let Some(secret) = &self.client_secret {
+            // client_secret_basic
+            request = request.basic_auth(&self.client_id, Some(secret));
+        }
+
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(OidcError::Provider(format!("token endpoint returned {}: {}", status, body)));
+        }
+
+        let tokens: TokenEndpointResponse = response.json().await?;
+        tokens
+            .id_token
+            .ok_or_else(|| OidcError::Provider("token response has no id_token".to_string()))
+    }
+
+    async fn fetch_jwks(&self) -> Result<JwkSet, OidcError> {
+        let discovery = self.discovery().await?;
+        let jwks: JwkSet = self.http.get(&discovery.jwks_uri).send().await?.error_for_status()?.json().await?;
+        *self.jwks.write().unwrap() = Some(jwks.clone());
+        Ok(jwks)
+    }
+
+    // Validasi ID token: signature dengan JWKS IdP, iss, aud, exp dan nonce
+    pub async fn validate_id_token(&self, id_token: &str, expected_nonce: &str) -> Result<IdTokenClaims, OidcError> {
+        let header = decode_header(id_token).map_err(|err| OidcError::InvalidIdToken(err.to_string()))?;
+
+        // ID token harus ditandatangani dengan key asimetris IdP, bukan HMAC
+        if matches!(header.alg, Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512) {
+            return Err(OidcError::InvalidIdToken("symmetric algorithms are not accepted".to_string()));
+        }
+
+        let cached = self.jwks.read().unwrap().clone();
+        let jwks = match cached {
+            Some(jwks) => jwks,
+            None => self.fetch_jwks().await?,
+        };
+
+        // Key baru di IdP (rotasi): ambil ulang JWKS sekali
+        let jwk = match find_key(&jwks, header.kid.as_deref()) {
+            Some(jwk) => jwk,
+            None => {
+                let jwks = self.fetch_jwks().await?;
+                find_key(&jwks, header.kid.as_deref())
+                    .ok_or_else(|| OidcError::InvalidIdToken("no matching signing key".to_string()))?
+            }
+        };
+
+        // `iss` dicocokkan dengan nilai dari discovery (sudah dicek sama dengan OIDC_ISSUER)
+        let discovery = self.discovery().await?;
+        let key = DecodingKey::from_jwk(&jwk).map_err(|err| OidcError::InvalidIdToken(err.to_string()))?;
+        let mut validation = Validation::new(header.alg);
+        validation.set_issuer(&[&discovery.issuer]);
+        validation.set_audience(&[&self.client_id]);
+        validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+
+        let claims = decode::<IdTokenClaims>(id_token, &key, &validation)
+            .map_err(|err| OidcError::InvalidIdToken(err.to_string()))?
+            .claims;
+
+        if claims.nonce.as_deref() != Some(expected_nonce) {
+            return Err(OidcError::InvalidIdToken("nonce mismatch".to_string()));
+        }
+
+        Ok(claims)
+    }
+}
+
+// Tanpa kid hanya diterima kalau JWKS cuma berisi satu key
+fn find_key(jwks: &JwkSet, kid: Option<&str>) -> Option<jsonwebtoken::jwk::Jwk> {
+    match kid {
+        Some(kid) => jwks.find(kid).cloned(),
+        None if jwks.keys.len() == 1 => jwks.keys.first().cloned(),
+        None => None,
+    }
+}
@@ -0,0 +1,180 @@
+use async_trait::async_trait;
+use rand::{rngs::OsRng, Rng};
+use serde::Serialize;
+use std::env;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+use super::mailer::{Email, MailError, Mailer};
+
+// Pengiriman kode login sekali pakai (OTP) lewat channel yang bisa diganti.
+//   OTP_EMAIL_CHANNEL   "email" (default, lewat mailer) atau "console"
+//   OTP_SMS_CHANNEL     "sms" (default kalau SMS_API_URL diisi) atau "console"
+// Provider SMS dipanggil lewat HTTP: POST SMS_API_URL dengan body JSON {"to", "message"}
+// dan header Authorization: Bearer SMS_API_TOKEN (opsional). Gateway asli cukup dibungkus
+// endpoint seperti ini, dan untuk development/test bisa diganti server lokal.
+
+pub const CHANNEL_EMAIL: &str = "email";
+pub const CHANNEL_SMS: &str = "sms";
+
+// Kode 6 digit dari CSPRNG, termasuk leading zero
+pub fn generate_code() -> String {
+    format!("{:06}", OsRng.gen_range(0..1_000_000))
+}
+
+// Normalisasi nomor telepon ke E.164: hapus spasi, tanda hubung dan kurung,
+// lalu wajib diawali "+" dengan 8-15 digit
+pub fn normalize_phone(phone: &str) -> Option<String> {
+    let cleaned: String = phone
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '-' | '(' | ')' | '.'))
+        .collect();
+    let digits = cleaned.strip_prefix('+')?;
+    if (8..=15).contains(&digits.len()) && digits.chars().all(|c| c.is_ascii_digit()) && !digits.starts_with('0') {
+        Some(cleaned)
+    } else {
+        None
+    }
+}
+
+#[derive(Debug)]
+pub enum DeliveryError {
+    Mail(MailError),
+    Http(reqwest::Error),
+    Provider(String),
+}
+
+impl fmt::Display for DeliveryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeliveryError::Mail(err) => write!(f, "{}", err),
+            DeliveryError::Http(err) => write!(f, "SMS request failed: {}", err),
+            DeliveryError::Provider(msg) => write!(f, "SMS provider error: {}", msg),
+        }
+    }
+}
+
+impl From<MailError> for DeliveryError {
+    fn from(err: MailError) -> Self {
+        DeliveryError::Mail(err)
+    }
+}
+
+impl From<reqwest::Error> for DeliveryError {
+    fn from(err: reqwest::Error) -> Self {
+        DeliveryError::Http(err)
+    }
+}
+
+#[async_trait]
+pub trait OtpChannel: Send + Sync {
+    // `destination` adalah email atau nomor E.164, tergantung channel
+    async fn deliver(&self, destination: &str, code: &str, ttl_secs: i64) -> Result<(), DeliveryError>;
+}
+
+fn message(code: &str, ttl_secs: i64) -> String {
+    format!("Your sign-in code is {}. It expires in {} minutes.", code, ttl_secs / 60)
+}
+
+pub struct EmailOtpChannel {
+    mailer: Arc<dyn Mailer>,
+}
+
+#[async_trait]
+impl OtpChannel for EmailOtpChannel {
+    async fn deliver(&self, destination: &str, code: &str, ttl_secs: i64) -> Result<(), DeliveryError> {
+        let email = Email {
+            to: destination.to_string(),
+            subject: "Your sign-in code".to_string(),
+            body: format!("{}\n\nIf you did not request this, you can ignore this email.", message(code, ttl_secs)),
+        };
+        self.mailer.send(&email).await?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+struct SmsPayload<'a> {
+    to: &'a str,
+    message: &'a str,
+}
+
+pub struct SmsOtpChannel {
+    api_url: String,
+    api_token: Option<String>,
+    http: reqwest::Client,
+}
+
+impl SmsOtpChannel {
+    pub fn new(api_url: String, api_token: Option<String>) -> Self {
+        Self {
+            api_url,
+            api_token,
+            http: reqwest::Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()
+                .expect("Failed to build HTTP client"),
+        }
+    }
+}
+
+#[async_trait]
+impl OtpChannel for SmsOtpChannel {
+    async fn deliver(&self, destination: &str, code: &str, ttl_secs: i64) -> Result<(), DeliveryError> {
+        let text = message(code, ttl_secs);
+        let mut request = self.http.post(&self.api_url).json(&SmsPayload { to: destination, message: &text });
+        if let Some(token) = &self.api_token {
+            request = request.bearer_auth(token);
+        }
+
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            return Err(DeliveryError::Provider(format!("gateway returned {}", response.status())));
+        }
+        Ok(())
+    }
+}
+
+// Cetak kode ke stdout, hanya untuk development
+pub struct ConsoleOtpChannel {
+    channel: &'static str,
+}
+
+#[async_trait]
+impl OtpChannel for ConsoleOtpChannel {
+    async fn deliver(&self, destination: &str, code: &str, ttl_secs: i64) -> Result<(), DeliveryError> {
+        println!("[otp:{}] to={} {}", self.channel, destination, message(code, ttl_secs));
+        Ok(())
+    }
+}
+
+// Channel yang dipakai untuk masing-masing jenis tujuan
+pub struct OtpChannels {
+    pub email: Arc<dyn OtpChannel>,
+    pub sms: Arc<dyn OtpChannel>,
+}
+
+impl OtpChannels {
+    pub fn from_env(mailer: Arc<dyn Mailer>) -> Self {
+        let email: Arc<dyn OtpChannel> = match env::var("OTP_EMAIL_CHANNEL").unwrap_or(CHANNEL_EMAIL.to_string()).as_str() {
+            "console" => Arc::new(ConsoleOtpChannel { channel: CHANNEL_EMAIL }),
+            _ => Arc::new(EmailOtpChannel { mailer }),
+        };
+
+        let sms_api_url = env::var("SMS_API_URL").ok().filter(|v| !v.is_empty());
+        let default_sms = if sms_api_url.is_some() { CHANNEL_SMS } else { "console" };
+        let sms: Arc<dyn OtpChannel> = match env::var("OTP_SMS_CHANNEL").unwrap_or(default_sms.to_string()).as_str() {
+            "console" => Arc::new(ConsoleOtpChannel { channel: CHANNEL_SMS }),
+            _ => Arc::new(SmsOtpChannel::new(
+                sms_api_url.expect("SMS_API_URL must be set when OTP_SMS_CHANNEL=sms"),
+                env::var("SMS_API_TOKEN").ok().filter(|v| !v.is_empty()),
+            )),
+        };
+
+        Self { email, sms }
+    }
+
+    pub fn for_channel(&self, channel: &str) -> &Arc<dyn OtpChannel> {
+        if channel == CHANNEL_SMS { &self.sms } else { &self.email }
+    }
+}
@@ -0,0 +1,68 @@
+use chrono::{Duration, Utc};
+use sea_orm::*;
+use crate::entity::{personal_access_token, user};
+use super::{hash, jwt::Claims, scope};
+
+// Personal access token dikirim lewat Authorization: Bearer, awalan ini
+// membedakannya dari JWT
+pub const TOKEN_PREFIX: &str = "pat_";
+
+const VISIBLE_PREFIX_LEN: usize = TOKEN_PREFIX.len() + 8;
+
+// last_used_at tidak di-update di setiap request, cukup sekali per menit
+const LAST_USED_RESOLUTION_SECS: i64 = 60;
+
+// Generate token baru, return (token lengkap, awalan yang bisa ditampilkan)
+pub fn generate() -> (String, String) {
+    let token = format!("{}{}", TOKEN_PREFIX, hash::random_token(32));
+    let prefix = token[..VISIBLE_PREFIX_LEN].to_string();
+    (token, prefix)
+}
+
+// Cari token yang masih aktif beserta pemiliknya
+pub async fn authenticate(
+    db: &DatabaseConnection,
+    token: &str,
+) -> Result<Option<(personal_access_token::Model, user::Model)>, DbErr> {
+    let now = Utc::now();
+    let found = personal_access_token::Entity::find()
+        .filter(personal_access_token::Column::TokenHash.eq(hash::sha256_hex(token)))
+        .filter(personal_access_token::Column::RevokedAt.is_null())
+        .filter(personal_access_token::Column::ExpiresAt.gt(now))
+        .find_also_related(user::Entity)
+        .one(db)
+        .await?;
+
+    let Some((token_model, Some(user_model))) = found else {
+        return Ok(None);
+    };
+
+    let stale = token_model
+        .last_used_at
+        .is_none_or(|at| now - at > Duration::seconds(LAST_USED_RESOLUTION_SECS));
+    if stale {
+        personal_access_token::Entity::update_many()
+            .col_expr(personal_access_token::Column::LastUsedAt, sea_query::Expr::value(now))
+            .filter(personal_access_token::Column::Id.eq(token_model.id))
+            .exec(db)
+            .await?;
+    }
+
+    Ok(Some((token_model, user_model)))
+}
+
+// Identitas pemilik token, dibatasi ke scope token
+pub fn to_claims(token: &personal_access_token::Model, owner: &user::Model) -> Claims {
+    let now = Utc::now();
+    let mut claims = Claims::new_access_token(
+        owner.id,
+        owner.email.clone(),
+        owner.role.clone(),
+        owner.token_version,
+        token.expires_at - now,
+    );
+    claims.jti = format!("pat:{}", token.id);
+    claims.token_type = "personal_access_token".to_string();
+    claims.scopes = Some(scope::parse(&token.scopes));
+    claims
+}
@@ -0,0 +1,29 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use sha2::{Digest, Sha256};
+use super::hash;
+
+// PKCE (RFC 7636), hanya method S256 yang didukung
+pub const METHOD_S256: &str = "S256";
+
+// code_challenge harus BASE64URL(SHA256(...)) = 43 karakter
+pub fn is_valid_challenge(challenge: &str) -> bool {
+    challenge.len() == 43 && URL_SAFE_NO_PAD.decode(challenge).is_ok()
+}
+
+// code_challenge untuk verifier yang kita buat sendiri (saat jadi client OIDC)
+pub fn challenge_s256(verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+}
+
+// code_verifier 43-128 karakter unreserved, lalu dicocokkan dengan challenge
+pub fn verify_s256(verifier: &str, challenge: &str) -> bool {
+    let valid_verifier = (43..=128).contains(&verifier.len())
+        && verifier
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_' | '~'));
+    if !valid_verifier {
+        return false;
+    }
+
+    hash::constant_time_eq(&challenge_s256(verifier), challenge)
+}
@@ -0,0 +1,38 @@
+// Scope yang bisa diberikan ke token terbatas (personal access token, dst).
+// Token tanpa scope (login biasa) punya akses penuh sesuai role user.
+pub const USERS_READ: &str = "users:read";
+pub const USERS_WRITE: &str = "users:write";
+pub const ADMIN: &str = "admin";
+// Kelola akun sendiri: sesi, personal access token, API key, status MFA
+pub const ACCOUNT: &str = "account";
+
+pub const KNOWN: &[&str] = &[USERS_READ, USERS_WRITE, ADMIN, ACCOUNT];
+
+// Parse daftar scope dipisah spasi, tanpa duplikat
+pub fn parse(value: &str) -> Vec<String> {
+    let mut scopes: Vec<String> = Vec::new();
+    for scope in value.split_whitespace() {
+        if !scopes.iter().any(|s| s == scope) {
+            scopes.push(scope.to_string());
+        }
+    }
+    scopes
+}
+
+// Gabungkan kembali untuk disimpan di database
+pub fn join(scopes: &[String]) -> String {
+    scopes.join(" ")
+}
+
+// Scope pertama yang tidak dikenal, kalau ada
+pub fn find_unknown(scopes: &[String]) -> Option<&str> {
+    scopes
+        .iter()
+        .map(String::as_str)
+        .find(|scope| !KNOWN.contains(scope))
+}
+
+// true kalau semua scope di `requested` ada di `allowed`
+pub fn is_subset(requested: &[String], allowed: &[String]) -> bool {
+    requested.iter().all(|scope| allowed.contains(scope))
+}
@@ -0,0 +1,60 @@
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use rand::{rngs::OsRng, RngCore};
+use std::env;
+
+// Enkripsi secret yang harus bisa dibaca lagi oleh server (mis. seed TOTP), AES-256-GCM.
+// MFA_ENCRYPTION_KEY: 32 byte di-encode base64, buat dengan `openssl rand -base64 32`.
+// Tanpa key ini fitur MFA dimatikan.
+//
+// Format tersimpan: base64(nonce 12 byte || ciphertext + tag). `context` dipakai sebagai
+// associated data, jadi ciphertext milik satu baris/user tidak bisa dipindah ke baris lain.
+
+const NONCE_LEN: usize = 12;
+
+pub struct SecretBox {
+    cipher: Aes256Gcm,
+}
+
+impl SecretBox {
+    // None kalau MFA_ENCRYPTION_KEY tidak diisi
+    pub fn from_env() -> Option<Self> {
+        let encoded = env::var("MFA_ENCRYPTION_KEY").ok().filter(|v| !v.is_empty())?;
+        let key = STANDARD
+            .decode(encoded.trim())
+            .ok()
+            .filter(|key| key.len() == 32)
+            .expect("MFA_ENCRYPTION_KEY must be 32 bytes encoded as base64");
+
+        Some(Self {
+            cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key)),
+        })
+    }
+
+    pub fn encrypt(&self, plaintext: &[u8], context: &str) -> String {
+        let mut nonce = [0u8; NONCE_LEN];
+        OsRng.fill_bytes(&mut nonce);
+
+        let ciphertext = self
+            .cipher
+            .encrypt(Nonce::from_slice(&nonce), Payload { msg: plaintext, aad: context.as_bytes() })
+            .expect("AES-GCM encryption failed");
+
+        let mut out = nonce.to_vec();
+        out.extend_from_slice(&ciphertext);
+        STANDARD.encode(out)
+    }
+
+    // None kalau data rusak, key salah atau context tidak cocok
+    pub fn decrypt(&self, value: &str, context: &str) -> Option<Vec<u8>> {
+        let raw = STANDARD.decode(value).ok()?;
+        if raw.len() <= NONCE_LEN {
+            return None;
+        }
+        let (nonce, ciphertext) = raw.split_at(NONCE_LEN);
+        self.cipher
+            .decrypt(Nonce::from_slice(nonce), Payload { msg: ciphertext, aad: context.as_bytes() })
+            .ok()
+    }
+}
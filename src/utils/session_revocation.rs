@@ -0,0 +1,50 @@
+use sea_orm::*;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use crate::entity::user_session;
+
+// Cache status revoke user_sessions supaya JwtMiddleware tidak query DB di setiap request.
+// Access token yang terbit dalam satu session membawa sid yang sama, jadi semuanya ikut mati
+// begitu session di-revoke. Revoke di instance ini langsung terlihat; instance lain paling lambat setelah TTL.
+pub struct SessionRevocationCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<String, (bool, Instant)>>,
+}
+
+impl SessionRevocationCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // true kalau session sudah di-revoke atau sudah tidak ada
+    pub async fn is_revoked(&self, db: &DatabaseConnection, session_id: &str) -> Result<bool, DbErr> {
+        if let Some((revoked, cached_at)) = self.entries.lock().unwrap().get(session_id)
+            && cached_at.elapsed() < self.ttl
+        {
+            return Ok(*revoked);
+        }
+
+        let revoked = match user_session::Entity::find_by_id(session_id.to_string()).one(db).await? {
+            Some(session) => session.revoked_at.is_some(),
+            None => true,
+        };
+
+        let mut entries = self.entries.lock().unwrap();
+        // Buang entry basi supaya map tidak tumbuh terus
+        entries.retain(|_, (_, cached_at)| cached_at.elapsed() < self.ttl);
+        entries.insert(session_id.to_string(), (revoked, Instant::now()));
+        Ok(revoked)
+    }
+
+    // Dipanggil setelah session di-revoke di database
+    pub fn mark_revoked(&self, session_id: &str) {
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(session_id.to_string(), (true, Instant::now()));
+    }
+}
@@ -0,0 +1,167 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sea_orm::*;
+use std::collections::HashMap;
+use std::env;
+use std::sync::{Arc, Mutex};
+use crate::entity::server_session;
+use crate::utils::jwt::{self, Claims};
+
+// Data session server-side (AUTH_MODE=session). Key-nya adalah SHA-256 dari
+// session id di cookie, jadi kebocoran storage tidak membocorkan cookie yang valid.
+#[derive(Clone, Debug)]
+pub struct ServerSession {
+    pub id_hash: String,
+    // user_sessions.id (daftar device)
+    pub session_id: String,
+    pub user_id: i32,
+    pub email: String,
+    pub role: String,
+    pub token_version: i32,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl ServerSession {
+    // Bentuk Claims yang sama dengan access token, supaya handler tidak peduli mode auth
+    pub fn to_claims(&self) -> Claims {
+        Claims {
+            sub: self.user_id.to_string(),
+            email: self.email.clone(),
+            role: self.role.clone(),
+            iss: jwt::issuer(),
+            aud: jwt::audience(),
+            exp: self.expires_at.timestamp(),
+            nbf: self.created_at.timestamp(),
+            iat: self.created_at.timestamp(),
+            jti: self.session_id.clone(),
+            sid: Some(self.session_id.clone()),
+            token_type: "access".to_string(),
+            ver: self.token_version,
+            act: None,
+            scopes: None,
+            client_id: None,
+        }
+    }
+}
+
+// Implementasi dipilih lewat env SESSION_STORE ("database" atau "memory")
+#[async_trait]
+pub trait SessionStore: Send + Sync {
+    async fn create(&self, session: ServerSession) -> Result<(), DbErr>;
+    // Session yang sudah expired dianggap tidak ada
+    async fn get(&self, id_hash: &str) -> Result<Option<ServerSession>, DbErr>;
+    // Hapus session berdasarkan user_sessions.id (revoke dari daftar device)
+    async fn delete_by_session_id(&self, session_id: &str) -> Result<(), DbErr>;
+    async fn prune_expired(&self) -> Result<u64, DbErr>;
+}
+
+pub struct DbSessionStore {
+    db: DatabaseConnection,
+}
+
+impl DbSessionStore {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl SessionStore for DbSessionStore {
+    async fn create(&self, session: ServerSession) -> Result<(), DbErr> {
+        let model = server_session::ActiveModel {
+            id: Set(session.id_hash),
+            session_id: Set(session.session_id),
+            user_id: Set(session.user_id),
+            email: Set(session.email),
+            role: Set(session.role),
+            token_version: Set(session.token_version),
+            expires_at: Set(session.expires_at),
+            created_at: Set(Some(session.created_at)),
+        };
+        server_session::Entity::insert(model).exec(&self.db).await?;
+        Ok(())
+    }
+
+    async fn get(&self, id_hash: &str) -> Result<Option<ServerSession>, DbErr> {
+        let found = server_session::Entity::find_by_id(id_hash.to_string())
+            .filter(server_session::Column::ExpiresAt.gt(Utc::now()))
+            .one(&self.db)
+            .await?;
+
+        Ok(found.map(|model| ServerSession {
+            id_hash: model.id,
+            session_id: model.session_id,
+            user_id: model.user_id,
+            email: model.email,
+            role: model.role,
+            token_version: model.token_version,
+            created_at: model.created_at.unwrap_or_default(),
+            expires_at: model.expires_at,
+        }))
+    }
+
+    async fn delete_by_session_id(&self, session_id: &str) -> Result<(), DbErr> {
+        server_session::Entity::delete_many()
+            .filter(server_session::Column::SessionId.eq(session_id))
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn prune_expired(&self) -> Result<u64, DbErr> {
+        let result = server_session::Entity::delete_many()
+            .filter(server_session::Column::ExpiresAt.lt(Utc::now()))
+            .exec(&self.db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+}
+
+// Versi in-memory, session hilang saat restart dan tidak dibagi antar instance
+#[derive(Default)]
+pub struct MemorySessionStore {
+    sessions: Mutex<HashMap<String, ServerSession>>,
+}
+
+#[async_trait]
+impl SessionStore for MemorySessionStore {
+    async fn create(&self, session: ServerSession) -> Result<(), DbErr> {
+        self.sessions.lock().unwrap().insert(session.id_hash.clone(), session);
+        Ok(())
+    }
+
+    async fn get(&self, id_hash: &str) -> Result<Option<ServerSession>, DbErr> {
+        let now = Utc::now();
+        Ok(self
+            .sessions
+            .lock()
+            .unwrap()
+            .get(id_hash)
+            .filter(|session| session.expires_at > now)
+            .cloned())
+    }
+
+    async fn delete_by_session_id(&self, session_id: &str) -> Result<(), DbErr> {
+        self.sessions
+            .lock()
+            .unwrap()
+            .retain(|_, session| session.session_id != session_id);
+        Ok(())
+    }
+
+    async fn prune_expired(&self) -> Result<u64, DbErr> {
+        let now = Utc::now();
+        let mut sessions = self.sessions.lock().unwrap();
+        let before = sessions.len();
+        sessions.retain(|_, session| session.expires_at > now);
+        Ok((before - sessions.len()) as u64)
+    }
+}
+
+pub fn from_env(db: &DatabaseConnection) -> Arc<dyn SessionStore> {
+    match env::var("SESSION_STORE").unwrap_or("database".to_string()).as_str() {
+        "memory" => Arc::new(MemorySessionStore::default()),
+        _ => Arc::new(DbSessionStore::new(db.clone())),
+    }
+}
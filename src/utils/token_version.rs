@@ -0,0 +1,60 @@
+use sea_orm::*;
+use sea_orm::sea_query::Expr;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use crate::entity::user;
+
+// Cache users.token_version supaya JwtMiddleware tidak query DB di setiap request.
+// Bump di instance ini langsung terlihat; instance lain melihatnya paling lambat setelah TTL.
+pub struct TokenVersionCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<i32, (i32, Instant)>>,
+}
+
+impl TokenVersionCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // Versi token terbaru untuk user, None kalau user sudah tidak ada
+    pub async fn current(&self, db: &DatabaseConnection, user_id: i32) -> Result<Option<i32>, DbErr> {
+        if let Some((version, cached_at)) = self.entries.lock().unwrap().get(&user_id)
+            && cached_at.elapsed() < self.ttl
+        {
+            return Ok(Some(*version));
+        }
+
+        let version = user::Entity::find_by_id(user_id)
+            .select_only()
+            .column(user::Column::TokenVersion)
+            .into_tuple::<i32>()
+            .one(db)
+            .await?;
+
+        let mut entries = self.entries.lock().unwrap();
+        match version {
+            Some(version) => {
+                entries.insert(user_id, (version, Instant::now()));
+            }
+            None => {
+                entries.remove(&user_id);
+            }
+        }
+        Ok(version)
+    }
+
+    // Naikkan token_version user, semua token yang sudah terbit jadi stale
+    pub async fn bump(&self, db: &DatabaseConnection, user_id: i32) -> Result<(), DbErr> {
+        user::Entity::update_many()
+            .col_expr(user::Column::TokenVersion, Expr::col(user::Column::TokenVersion).add(1))
+            .filter(user::Column::Id.eq(user_id))
+            .exec(db)
+            .await?;
+        self.entries.lock().unwrap().remove(&user_id);
+        Ok(())
+    }
+}
@@ -0,0 +1,65 @@
+use chrono::Utc;
+use rand::{rngs::OsRng, RngCore};
+use std::env;
+use totp_rs::{Algorithm, Secret, TOTP};
+use super::hash;
+
+// TOTP (RFC 6238) untuk MFA: SHA-1, 6 digit, step 30 detik, kompatibel dengan
+// Google Authenticator, 1Password, Authy, dll.
+// MFA_ISSUER: nama aplikasi yang tampil di authenticator (default "actix_server")
+
+const DIGITS: usize = 6;
+const STEP_SECS: u64 = 30;
+// Toleransi jam HP yang sedikit meleset: kode dari 1 step sebelum/sesudah diterima
+const SKEW_STEPS: i64 = 1;
+// 160 bit, sesuai rekomendasi RFC 4226
+const SECRET_LEN: usize = 20;
+
+pub fn generate_secret() -> Vec<u8> {
+    let mut secret = vec![0u8; SECRET_LEN];
+    OsRng.fill_bytes(&mut secret);
+    secret
+}
+
+fn issuer() -> String {
+    // ':' adalah pemisah di label otpauth://
+    env::var("MFA_ISSUER").unwrap_or("actix_server".to_string()).replace(':', "")
+}
+
+fn build(secret: &[u8], account_name: &str) -> TOTP {
+    TOTP::new_unchecked(
+        Algorithm::SHA1,
+        DIGITS,
+        0,
+        STEP_SECS,
+        secret.to_vec(),
+        Some(issuer()),
+        account_name.replace(':', ""),
+    )
+}
+
+// Secret dalam base32, untuk dimasukkan manual ke authenticator
+pub fn secret_base32(secret: &[u8]) -> String {
+    Secret::Raw(secret.to_vec()).to_encoded().to_string()
+}
+
+// URI otpauth://totp/... untuk QR code
+pub fn provisioning_uri(secret: &[u8], account_name: &str) -> String {
+    build(secret, account_name).get_url()
+}
+
+// Cek kode terhadap waktu sekarang. Return nomor step yang cocok, yang harus disimpan
+// sebagai `last_used_step` supaya kode yang sama tidak bisa dipakai dua kali.
+pub fn verify(secret: &[u8], code: &str, last_used_step: Option<i64>) -> Option<i64> {
+    let code = code.trim();
+    if code.len() != DIGITS || !code.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let totp = build(secret, "");
+    let current = Utc::now().timestamp() / STEP_SECS as i64;
+
+    (current - SKEW_STEPS..=current + SKEW_STEPS)
+        .filter(|step| last_used_step.is_none_or(|last| *step > last))
+        .find(|step| hash::constant_time_eq(&totp.generate(*step as u64 * STEP_SECS), code))
+}
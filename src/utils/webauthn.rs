@@ -0,0 +1,21 @@
+use std::env;
+use webauthn_rs::prelude::{Url, Webauthn, WebauthnBuilder};
+
+// Konfigurasi relying party WebAuthn (passkey / security key):
+//   WEBAUTHN_RP_ID       domain yang memiliki credential, mis. example.com (default localhost)
+//   WEBAUTHN_RP_ORIGIN   origin frontend yang menjalankan navigator.credentials,
+//                        mis. https://app.example.com (default http://localhost:8080)
+//   WEBAUTHN_RP_NAME     nama yang tampil di dialog passkey (default "actix_server")
+// Origin http://localhost diterima supaya ceremony bisa dijalankan oleh software
+// authenticator di test.
+
+pub fn from_env() -> Webauthn {
+    let rp_id = env::var("WEBAUTHN_RP_ID").unwrap_or("localhost".to_string());
+    let rp_origin = env::var("WEBAUTHN_RP_ORIGIN").unwrap_or("http://localhost:8080".to_string());
+    let rp_name = env::var("WEBAUTHN_RP_NAME").unwrap_or("actix_server".to_string());
+    let rp_origin = Url::parse(&rp_origin).expect("WEBAUTHN_RP_ORIGIN must be a valid URL");
+
+    WebauthnBuilder::new(&rp_id, &rp_origin)
+        .and_then(|builder| builder.rp_name(&rp_name).build())
+        .expect("Invalid WebAuthn relying party configuration")
+}